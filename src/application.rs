@@ -1,15 +1,26 @@
 use std::{
-    fs::{self, File},
+    fs,
     io::{self, Write},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    benchmark_runner::{BenchmarkRunner, BenchmarkSuite, GeneratorKind, ParameterRange, Solver},
     error::Error,
-    graph::{penalty_digraph::PenaltyDigraph, Graph},
+    export::{graph_to_dot, graph_to_svg, penalty_digraph_to_dot},
+    graph::{
+        analysis::GraphAnalysis,
+        compact_graph::CompactGraph,
+        penalty_digraph::{FasMethod, PenaltyDigraph},
+        Graph,
+    },
     graph_builder::GraphBuilder,
+    memory::{MemoryTracker, PhaseMemory},
+    plot::{ChartAxis, LineChart},
+    report::{read_benchmark_results, BenchmarkReport},
+    verify::read_solution_file,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,6 +31,86 @@ pub struct BenchmarkStats {
     pub loading_elapsed: u128,
     pub reduction_elapsed: u128,
     pub ordering_elapsed: u128,
+    #[serde(default)]
+    pub graph_memory_bytes: usize,
+    #[serde(default)]
+    pub compact_graph_memory_bytes: usize,
+    #[serde(default)]
+    pub generator: String,
+    #[serde(default)]
+    pub solver: String,
+    /// Number of measured graphs, the `*_elapsed` fields contain the mean of all measurements
+    #[serde(default)]
+    pub repetitions: usize,
+    #[serde(default)]
+    pub seeds: Vec<u64>,
+    #[serde(default)]
+    pub loading_ns: Option<SampleSummary>,
+    #[serde(default)]
+    pub reduction_ns: Option<SampleSummary>,
+    #[serde(default)]
+    pub ordering_ns: Option<SampleSummary>,
+    #[serde(default)]
+    pub number_of_crossings: Option<SampleSummary>,
+    /// Memory usage of the phases, the maximum of all measurements
+    #[serde(default)]
+    pub loading_memory: Option<PhaseMemory>,
+    #[serde(default)]
+    pub reduction_memory: Option<PhaseMemory>,
+    #[serde(default)]
+    pub ordering_memory: Option<PhaseMemory>,
+}
+
+/// Summary statistics of repeated measurements, together with the raw samples
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SampleSummary {
+    pub mean: f64,
+    pub median: f64,
+    /// Sample standard deviation, 0 for less than two samples
+    pub stddev: f64,
+    pub min: u64,
+    pub max: u64,
+    pub samples: Vec<u64>,
+}
+
+impl SampleSummary {
+    /// Computes the summary of the given samples
+    pub fn from_samples(samples: Vec<u64>) -> SampleSummary {
+        if samples.is_empty() {
+            return SampleSummary::default();
+        }
+
+        let mut sorted_samples = samples.clone();
+        sorted_samples.sort_unstable();
+        let number_of_samples = samples.len();
+        let middle = number_of_samples / 2;
+        let median = if number_of_samples.is_multiple_of(2) {
+            (sorted_samples[middle - 1] as f64 + sorted_samples[middle] as f64) / 2.0
+        } else {
+            sorted_samples[middle] as f64
+        };
+        let mean = samples.iter().map(|sample| *sample as f64).sum::<f64>() / number_of_samples as f64;
+        let stddev = if number_of_samples < 2 {
+            0.0
+        } else {
+            let squared_deviations: f64 = samples.iter().map(|sample| (*sample as f64 - mean).powi(2)).sum();
+            (squared_deviations / (number_of_samples - 1) as f64).sqrt()
+        };
+
+        SampleSummary {
+            mean,
+            median,
+            stddev,
+            min: sorted_samples[0],
+            max: sorted_samples[number_of_samples - 1],
+            samples,
+        }
+    }
+
+    /// Converts a summary of nanosecond samples to whole milliseconds (of the mean)
+    pub fn mean_millis(&self) -> u128 {
+        (self.mean / 1_000_000.0).round() as u128
+    }
 }
 
 /// This struct provides a number of methods that run different tests
@@ -36,94 +127,131 @@ impl Application {
     ///
     /// The generated graphs have different numbers of fixed and free nodes, ranging from 500 to 30,000
     pub fn run_tests_with_same_edges(&self) -> Result<Vec<BenchmarkStats>, Error> {
-        let number_of_edges = 100_000;
-        let node_step_size = 500;
-        let max_number_of_nodes = 30_000;
-
-        println!("----- Run tests on graphs with {number_of_edges} edges --------------------------------------------------");
-        let mut file = File::create("benchmark_results/benchmark_with_const_edges.json")?;
-        let mut benchmark_stats: Vec<BenchmarkStats> = Vec::new();
-
-        file.write(b"[\n")?;
-        for number_of_nodes in (node_step_size..=max_number_of_nodes).step_by(node_step_size) {
-            let benchmark = self.run_test_on_randomly_generated_graph(
-                number_of_nodes,
-                number_of_nodes,
-                number_of_edges,
-            )?;
-            let benchmark_json =
-                serde_json::to_string_pretty(&benchmark).expect("Converted to json.");
-            file.write(benchmark_json.as_bytes())?;
-            file.write(b",\n")?;
-            benchmark_stats.push(benchmark);
-        }
-        file.write(b"]")?;
-
-        Ok(benchmark_stats)
+        let number_of_nodes = ParameterRange::Range {
+            from: 500,
+            to: 30_000,
+            step: 500,
+        };
+
+        BenchmarkRunner::new(Application::random_graph_suite(
+            "const_edges",
+            number_of_nodes.clone(),
+            number_of_nodes,
+            ParameterRange::Value(100_000),
+            true,
+            "benchmark_with_const_edges",
+        ))
+        .run()
     }
 
-    /// Runs tests on randomly generated graphs with a fixed number of edges (100,000) and free nodes(5,000)
+    /// Runs tests on randomly generated graphs with a fixed number of edges (50,000) and free nodes(5,000)
     ///
     /// The generated graphs have different numbers of fixed nodes, ranging from 5,000 to 500,000
     pub fn run_tests_with_same_edges_and_free_nodes(&self) -> Result<Vec<BenchmarkStats>, Error> {
-        let number_of_free_nodes = 5_000;
-        let number_of_edges = 50_000;
-        let fixed_node_step_size = 5_000;
-        let max_number_of_fixed_nodes = 500_000;
-
-        println!("----- Run tests on graphs with {number_of_free_nodes} free nodes and {number_of_edges} edges --------------------------------------------------");
-        let mut file =
-            File::create("benchmark_results/benchmark_with_const_free_nodes_and_edges.json")?;
-        let mut benchmark_stats: Vec<BenchmarkStats> = Vec::new();
-
-        file.write(b"[\n")?;
-        for number_of_fixed_nodes in
-            (fixed_node_step_size..=max_number_of_fixed_nodes).step_by(fixed_node_step_size)
-        {
-            let benchmark = self.run_test_on_randomly_generated_graph(
-                number_of_fixed_nodes,
-                number_of_free_nodes,
-                number_of_edges,
-            )?;
-            let benchmark_json =
-                serde_json::to_string_pretty(&benchmark).expect("Converted to json.");
-            file.write(benchmark_json.as_bytes())?;
-            file.write(b",\n")?;
-            benchmark_stats.push(benchmark);
-        }
-        file.write(b"]")?;
-
-        Ok(benchmark_stats)
+        BenchmarkRunner::new(Application::random_graph_suite(
+            "const_free_nodes_and_edges",
+            ParameterRange::Range {
+                from: 5_000,
+                to: 500_000,
+                step: 5_000,
+            },
+            ParameterRange::Value(5_000),
+            ParameterRange::Value(50_000),
+            false,
+            "benchmark_with_const_free_nodes_and_edges",
+        ))
+        .run()
     }
 
     /// Runs tests on randomly generated graphs with a fixed number of fixed and free nodes (5,000)
     ///
     /// The generated graphs have different numbers of edges, ranging from 10,000 to 2,000,000
     pub fn run_tests_with_same_nodes(&self) -> Result<Vec<BenchmarkStats>, Error> {
-        let number_of_nodes = 5_000;
-        let edge_step_size = 10_000;
-        let max_number_of_edges = 2_000_000;
-
-        println!("----- Run tests on graphs with {number_of_nodes} fixed and free nodes --------------------------------------------------");
-        let mut file = File::create("benchmark_results/benchmark_with_const_nodes.json")?;
-        let mut benchmark_stats: Vec<BenchmarkStats> = Vec::new();
-
-        file.write(b"[\n")?;
-        for number_of_edges in (edge_step_size..=max_number_of_edges).step_by(edge_step_size) {
-            let benchmark = self.run_test_on_randomly_generated_graph(
-                number_of_nodes,
-                number_of_nodes,
-                number_of_edges,
-            )?;
-            let benchmark_json =
-                serde_json::to_string_pretty(&benchmark).expect("Converted to json.");
-            file.write(benchmark_json.as_bytes())?;
-            file.write(b",\n")?;
-            benchmark_stats.push(benchmark);
+        BenchmarkRunner::new(Application::random_graph_suite(
+            "const_nodes",
+            ParameterRange::Value(5_000),
+            ParameterRange::Value(5_000),
+            ParameterRange::Range {
+                from: 10_000,
+                to: 2_000_000,
+                step: 10_000,
+            },
+            false,
+            "benchmark_with_const_nodes",
+        ))
+        .run()
+    }
+
+    /// Runs the benchmark suite described in a JSON file
+    pub fn run_benchmark_suite(&self, filename: &str) -> Result<Vec<BenchmarkStats>, Error> {
+        BenchmarkRunner::from_file(filename)?.run()
+    }
+
+    /// Prints a comparison of the results in the given files and optionally exports it as CSV
+    pub fn report_benchmark_results(
+        &self,
+        filenames: &[String],
+        csv_output: Option<&str>,
+    ) -> Result<BenchmarkReport, Error> {
+        let report = BenchmarkReport::from_files(filenames)?;
+        print!("{report}");
+        if let Some(csv_output) = csv_output {
+            report.write_csv(csv_output)?;
         }
-        file.write(b"]")?;
 
-        Ok(benchmark_stats)
+        Ok(report)
+    }
+
+    /// Writes a chart of the timings in a result file (JSON array or JSON Lines) to an SVG file
+    pub fn chart_benchmark_results(&self, filename: &str, x_axis: ChartAxis, output: &str) -> Result<(), Error> {
+        LineChart::timing_chart(&read_benchmark_results(filename)?, x_axis).write_svg(output)
+    }
+
+    /// Draws a graph from a file with the free nodes ordered by a solution file (or by `sort_fas`, if there is none)
+    ///
+    /// The drawing is written as Graphviz DOT if the output file ends with `.dot` and as SVG otherwise.
+    pub fn draw_graph(&self, graph_file: &str, output: &str, solution_file: Option<&str>) -> Result<(), Error> {
+        let graph = GraphBuilder::build_graph_from_file(graph_file)?;
+        let ordering = match solution_file {
+            Some(solution_file) => read_solution_file(&graph, solution_file)?,
+            None => PenaltyDigraph::from_graph(&graph)
+                .sort_fas()
+                .iter()
+                .map(|node| node + graph.number_of_fixed_nodes())
+                .collect(),
+        };
+
+        let drawing = if output.ends_with(".dot") {
+            graph_to_dot(&graph, &ordering)?
+        } else {
+            graph_to_svg(&graph, &ordering)?
+        };
+        fs::write(output, drawing).map_err(Error::with_path(output))?;
+
+        Ok(())
+    }
+
+    /// Writes the penalty digraph of a graph from a file as Graphviz DOT, highlighting the feedback arcs of a FAS method
+    pub fn draw_penalty_digraph(&self, graph_file: &str, output: &str, method: FasMethod) -> Result<(), Error> {
+        let graph = GraphBuilder::build_graph_from_file(graph_file)?;
+        let penalty_digraph = PenaltyDigraph::from_graph(&graph);
+        let ordering = penalty_digraph.compute_fas(method);
+        let dot = penalty_digraph_to_dot(&penalty_digraph, &ordering)?;
+        fs::write(output, dot).map_err(Error::with_path(output))?;
+
+        Ok(())
+    }
+
+    /// Prints the analysis of a graph from a file, either readable or as JSON
+    pub fn analyze_graph(&self, graph_file: &str, as_json: bool) -> Result<GraphAnalysis, Error> {
+        let analysis = GraphBuilder::build_graph_from_file(graph_file)?.analyze();
+        if as_json {
+            println!("{}", serde_json::to_string_pretty(&analysis)?);
+        } else {
+            println!("{analysis}");
+        }
+
+        Ok(analysis)
     }
 
     /// Runs tests on randomly generated graphs with a specific property.
@@ -151,9 +279,9 @@ impl Application {
 
         for _ in 0..number_of_test_cases {
             let graph =
-                GraphBuilder::build_graph_with_fixed_nodes_and_no_crossings(number_of_fixed_nodes);
+                GraphBuilder::build_graph_with_fixed_nodes_and_no_crossings(number_of_fixed_nodes)?;
             let penalty_digraph = PenaltyDigraph::from_graph(&graph);
-            let ordering = penalty_digraph
+            let ordering: Vec<usize> = penalty_digraph
                 .sort_fas()
                 .iter()
                 .map(|e| e + graph.number_of_fixed_nodes())
@@ -176,12 +304,18 @@ impl Application {
 
     /// Loads the graphs from /ressources/tiny_test_set and performs the algorithm on that
     pub fn run_tests_on_tiny_test_set(&self) -> Result<(), Error> {
-        let paths = fs::read_dir("ressources/tiny_test_set")?;
-
-        let mut filenames: Vec<String> = paths
-            .flatten()
-            .map(|entry| entry.path().to_str().unwrap().to_owned())
-            .collect();
+        let test_set_directory = "ressources/tiny_test_set";
+        let paths = fs::read_dir(test_set_directory).map_err(Error::with_path(test_set_directory))?;
+
+        let mut filenames = Vec::new();
+        for entry in paths {
+            let path = entry?.path();
+            let filename = path.to_str().ok_or(Error::ValueError(format!(
+                "The path {} is not valid unicode",
+                path.display()
+            )))?;
+            filenames.push(filename.to_owned());
+        }
         filenames.sort();
 
         for filename in filenames {
@@ -194,6 +328,42 @@ impl Application {
 
 // PRIVATE METHODS
 impl Application {
+    /// Constructs a suite that runs `sort_fas` once on random graphs for each configuration
+    ///
+    /// The results are written to `benchmark_results/<results_name>.jsonl` and `benchmark_results/<results_name>.json`,
+    /// the chart of the timings to `benchmark_results/<results_name>.svg`.
+    fn random_graph_suite(
+        name: &str,
+        number_of_fixed_nodes: ParameterRange,
+        number_of_free_nodes: ParameterRange,
+        number_of_edges: ParameterRange,
+        couple_node_ranges: bool,
+        results_name: &str,
+    ) -> BenchmarkSuite {
+        BenchmarkSuite {
+            name: name.to_string(),
+            generator: GeneratorKind::Random,
+            number_of_fixed_nodes,
+            number_of_free_nodes,
+            number_of_edges,
+            couple_node_ranges,
+            repetitions: 1,
+            seed: None,
+            solvers: vec![Solver::Fas {
+                method: FasMethod::SortFas,
+                by_components: false,
+            }],
+            time_limit: 0,
+            exact_threshold: 0,
+            compute_number_of_crossings: false,
+            output: format!("benchmark_results/{results_name}.jsonl"),
+            json_array_output: Some(format!("benchmark_results/{results_name}.json")),
+            resume: false,
+            chart_output: Some(format!("benchmark_results/{results_name}.svg")),
+            chart_axis: None,
+        }
+    }
+
     /// Loads a graph from a file and tests the algorithm on that graph
     fn run_test_on_graph_from_file(
         &self,
@@ -203,10 +373,12 @@ impl Application {
     ) -> Result<BenchmarkStats, Error> {
         print!("Loading graph from file '{}'...", filename);
         io::stdout().flush()?;
+        let mut memory_tracker = MemoryTracker::start();
         let begin = Instant::now();
         let graph = GraphBuilder::build_graph_from_file(filename)?;
-        let loading_elapsed = begin.elapsed().as_millis();
-        println!(" done! ({} ms)", loading_elapsed);
+        let loading_elapsed = begin.elapsed();
+        let loading_memory = memory_tracker.finish_phase();
+        println!(" done! ({} ms)", loading_elapsed.as_millis());
         println!(
             "The graph has {} fixed nodes, {} free nodes and {} edges.",
             graph.number_of_fixed_nodes(),
@@ -217,69 +389,59 @@ impl Application {
         self.run_test_on_graph(
             &graph,
             loading_elapsed,
+            loading_memory,
             should_print_ordering,
             should_compute_number_of_crossings,
         )
     }
 
-    /// Generates a random graph and tests the algorithm on that graph
-    fn run_test_on_randomly_generated_graph(
-        &self,
-        number_of_fixed_nodes: usize,
-        number_of_free_nodes: usize,
-        number_of_edges: usize,
-    ) -> Result<BenchmarkStats, Error> {
-        let begin = Instant::now();
-        print!(
-            "Generating graph with {} fixed nodes, {} free nodes and {} edges...",
-            number_of_fixed_nodes, number_of_free_nodes, number_of_edges
-        );
-        io::stdout().flush()?;
-        let graph = GraphBuilder::build_random_graph(
-            number_of_fixed_nodes,
-            number_of_free_nodes,
-            number_of_edges,
-        )?;
-        let loading_elapsed = begin.elapsed().as_millis();
-        println!(" done! ({} ms)", loading_elapsed);
-
-        self.run_test_on_graph(&graph, loading_elapsed, false, false)
-    }
-
     /// Tests the algorithm on a given graph
     fn run_test_on_graph(
         &self,
         graph: &Graph,
-        loading_elapsed: u128,
+        loading_elapsed: Duration,
+        loading_memory: PhaseMemory,
         should_print_ordering: bool,
         should_compute_number_of_crossings: bool,
     ) -> Result<BenchmarkStats, Error> {
-        let begin = Instant::now();
         print!("Computing penalty digraph...");
         io::stdout().flush()?;
-        let penalty_digraph = PenaltyDigraph::from_graph(&graph);
-        let reduction_elapsed = begin.elapsed().as_millis();
-        println!(" done! ({} ms)", reduction_elapsed);
+        let mut memory_tracker = MemoryTracker::start();
+        let begin = Instant::now();
+        let compact_graph = CompactGraph::from_graph(graph);
+        let penalty_digraph = PenaltyDigraph::from_compact_graph(&compact_graph);
+        let reduction_elapsed = begin.elapsed();
+        let reduction_memory = memory_tracker.finish_phase();
+        println!(" done! ({} ms)", reduction_elapsed.as_millis());
+
+        let graph_memory_bytes = graph.memory_usage();
+        let compact_graph_memory_bytes = compact_graph.memory_usage();
+        println!(
+            "The graph uses {} bytes, its compact representation uses {} bytes.",
+            graph_memory_bytes, compact_graph_memory_bytes
+        );
 
         if should_compute_number_of_crossings {
             let begin = Instant::now();
             print!("Computing number of crossings for default ordering...");
             io::stdout().flush()?;
-            let number_of_crossings = graph.compute_number_of_crossings_with_default_ordering()?;
+            let number_of_crossings = compact_graph.compute_number_of_crossings_with_default_ordering();
             println!(" done! ({} ms)", begin.elapsed().as_millis());
             println!("The graph has {} crossings.", number_of_crossings);
         }
 
-        let begin = Instant::now();
         print!("Computing ordering for free nodes...");
         io::stdout().flush()?;
+        let mut memory_tracker = MemoryTracker::start();
+        let begin = Instant::now();
         let ordering: Vec<usize> = penalty_digraph
             .sort_fas()
             .into_iter()
             .map(|e| e + graph.number_of_fixed_nodes())
             .collect();
-        let ordering_elapsed = begin.elapsed().as_millis();
-        println!(" done! ({} ms)", ordering_elapsed);
+        let ordering_elapsed = begin.elapsed();
+        let ordering_memory = memory_tracker.finish_phase();
+        println!(" done! ({} ms)", ordering_elapsed.as_millis());
 
         if should_print_ordering {
             let ordering_with_actual_node_name: Vec<usize> =
@@ -287,27 +449,43 @@ impl Application {
             println!("Ordering: {:?}", ordering_with_actual_node_name);
         }
 
+        let mut number_of_crossings = None;
         if should_compute_number_of_crossings {
             let begin = Instant::now();
             print!("Computing number of crossings for computed ordering...");
             io::stdout().flush()?;
-            let number_of_crossings = graph.compute_number_of_crossings_for_ordering(&ordering)?;
+            let number_of_crossings_for_ordering = compact_graph.compute_number_of_crossings_for_ordering(&ordering)?;
             println!(" done! ({} ms)", begin.elapsed().as_millis());
             println!(
                 "The graph has {} crossings with the new ordering.",
-                number_of_crossings
+                number_of_crossings_for_ordering
             );
+            number_of_crossings = Some(number_of_crossings_for_ordering);
         }
 
-        println!("");
+        println!();
 
         Ok(BenchmarkStats {
             number_of_fixed_nodes: graph.number_of_fixed_nodes(),
             number_of_free_nodes: graph.number_of_free_nodes(),
             number_of_edges: graph.number_of_edges(),
-            loading_elapsed,
-            reduction_elapsed,
-            ordering_elapsed,
+            loading_elapsed: loading_elapsed.as_millis(),
+            reduction_elapsed: reduction_elapsed.as_millis(),
+            ordering_elapsed: ordering_elapsed.as_millis(),
+            graph_memory_bytes,
+            compact_graph_memory_bytes,
+            generator: "file".to_string(),
+            solver: FasMethod::SortFas.to_string(),
+            repetitions: 1,
+            seeds: Vec::new(),
+            loading_ns: Some(SampleSummary::from_samples(vec![loading_elapsed.as_nanos() as u64])),
+            reduction_ns: Some(SampleSummary::from_samples(vec![reduction_elapsed.as_nanos() as u64])),
+            ordering_ns: Some(SampleSummary::from_samples(vec![ordering_elapsed.as_nanos() as u64])),
+            number_of_crossings: number_of_crossings
+                .map(|number_of_crossings| SampleSummary::from_samples(vec![number_of_crossings as u64])),
+            loading_memory: Some(loading_memory),
+            reduction_memory: Some(reduction_memory),
+            ordering_memory: Some(ordering_memory),
         })
     }
 }
@@ -0,0 +1,678 @@
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{BenchmarkStats, SampleSummary},
+    error::Error,
+    graph::{
+        compact_graph::CompactGraph,
+        penalty_digraph::{FasMethod, PenaltyDigraph},
+        Graph,
+    },
+    graph_builder::GraphBuilder,
+    heuristics::{iterated_greedy::IteratedGreedy, simulated_annealing::SimulatedAnnealing},
+    memory::{MemoryTracker, PhaseMemory},
+    plot::{ChartAxis, LineChart},
+};
+
+/// Description of a benchmark suite, usually loaded from a JSON file
+///
+/// For every combination of the parameter values, `repetitions` graphs are generated and every solver is run on each of them.
+/// Example:
+/// ```json
+/// {
+///     "name": "const_edges",
+///     "generator": { "kind": "random" },
+///     "number_of_fixed_nodes": { "from": 500, "to": 30000, "step": 500 },
+///     "number_of_free_nodes": { "from": 500, "to": 30000, "step": 500 },
+///     "number_of_edges": 100000,
+///     "couple_node_ranges": true,
+///     "solvers": ["sort_fas", "kwik_sort_by_components"],
+///     "output": "benchmark_results/benchmark_with_const_edges.jsonl",
+///     "json_array_output": "benchmark_results/benchmark_with_const_edges.json",
+///     "chart_output": "benchmark_results/benchmark_with_const_edges.svg"
+/// }
+/// ```
+/// With `couple_node_ranges`, the values of both node parameters are paired up instead of forming all combinations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkSuite {
+    pub name: String,
+    #[serde(default)]
+    pub generator: GeneratorKind,
+    pub number_of_fixed_nodes: ParameterRange,
+    pub number_of_free_nodes: ParameterRange,
+    pub number_of_edges: ParameterRange,
+    /// Pairs the i-th number of fixed nodes with the i-th number of free nodes, so both node parameters are varied together.
+    /// Both ranges must have the same number of values.
+    #[serde(default)]
+    pub couple_node_ranges: bool,
+    #[serde(default = "default_repetitions")]
+    pub repetitions: usize,
+    /// Seed of the first generated graph, the following graphs (of all configurations and repetitions) use the next seeds.
+    /// A random seed is chosen if `None`.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_solvers")]
+    pub solvers: Vec<Solver>,
+    /// Time limit of the metaheuristics in milliseconds
+    #[serde(default = "default_time_limit")]
+    pub time_limit: u64,
+    /// Components of at most this size are solved exactly by the `*_by_components` solvers
+    #[serde(default)]
+    pub exact_threshold: usize,
+    #[serde(default)]
+    pub compute_number_of_crossings: bool,
+    /// JSON Lines file, every `BenchmarkStats` is written to its own line as soon as it is measured
+    pub output: String,
+    /// If set, all results are additionally written to this file as a JSON array after the suite is finished
+    #[serde(default)]
+    pub json_array_output: Option<String>,
+    /// Keeps the results already in the output file and only runs the missing configurations and solvers.
+    /// Requires a seed, so that the skipped configurations are identified by the seeds of their graphs.
+    #[serde(default)]
+    pub resume: bool,
+    /// If set, a chart of the timings is written to this SVG file after the suite is finished
+    #[serde(default)]
+    pub chart_output: Option<String>,
+    /// x-axis of the chart, by default the parameter that is varied by the suite
+    #[serde(default)]
+    pub chart_axis: Option<ChartAxis>,
+}
+
+/// Values of a suite parameter, either a single value or all values `from, from + step, ..., to` (inclusive)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParameterRange {
+    Value(usize),
+    Range { from: usize, to: usize, step: usize },
+}
+
+/// Graph generator of a suite, see the corresponding functions of `GraphBuilder`
+///
+/// The generators with a fixed degree use `number_of_edges / number_of_free_nodes` as the degree.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GeneratorKind {
+    #[default]
+    Random,
+    PowerLaw { exponent: f64 },
+    FixedDegree,
+    Geometric { spread: f64 },
+}
+
+/// Algorithm that computes the ordering of the free nodes in a benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Solver {
+    /// A FAS method on the whole penalty digraph, or on each strongly connected component if `by_components` is set
+    Fas { method: FasMethod, by_components: bool },
+    SimulatedAnnealing,
+    IteratedGreedy,
+}
+
+/// Runs a `BenchmarkSuite` and writes the results as JSON Lines of `BenchmarkStats`
+pub struct BenchmarkRunner {
+    suite: BenchmarkSuite,
+}
+
+/// Measurements of one solver on one graph
+struct Sample {
+    loading_ns: u64,
+    reduction_ns: u64,
+    ordering_ns: u64,
+    number_of_crossings: Option<usize>,
+    loading_memory: PhaseMemory,
+    reduction_memory: PhaseMemory,
+    ordering_memory: PhaseMemory,
+}
+
+// CONSTRUCTORS
+impl BenchmarkRunner {
+    /// Constructs a new `BenchmarkRunner` for a suite
+    pub fn new(suite: BenchmarkSuite) -> BenchmarkRunner {
+        BenchmarkRunner { suite }
+    }
+
+    /// Constructs a new `BenchmarkRunner` for a suite that is described in a JSON file
+    pub fn from_file(filename: &str) -> Result<BenchmarkRunner, Error> {
+        let suite = serde_json::from_str(&fs::read_to_string(filename).map_err(Error::with_path(filename))?)?;
+        Ok(BenchmarkRunner::new(suite))
+    }
+}
+
+// GETTERS
+impl BenchmarkRunner {
+    pub fn suite(&self) -> &BenchmarkSuite {
+        &self.suite
+    }
+}
+
+// PUBLIC METHODS
+impl BenchmarkRunner {
+    /// Returns all `(number_of_fixed_nodes, number_of_free_nodes, number_of_edges)` combinations of the suite
+    pub fn configurations(&self) -> Result<Vec<(usize, usize, usize)>, Error> {
+        let fixed_node_values = self.suite.number_of_fixed_nodes.values()?;
+        let free_node_values = self.suite.number_of_free_nodes.values()?;
+        let edge_values = self.suite.number_of_edges.values()?;
+
+        let node_values: Vec<(usize, usize)> = if self.suite.couple_node_ranges {
+            if fixed_node_values.len() != free_node_values.len() {
+                return Err(Error::ValueError(
+                    "Coupled node ranges must have the same number of values".to_string(),
+                ));
+            }
+            fixed_node_values.into_iter().zip(free_node_values).collect()
+        } else {
+            fixed_node_values
+                .iter()
+                .flat_map(|fixed| free_node_values.iter().map(|free| (*fixed, *free)))
+                .collect()
+        };
+
+        Ok(node_values
+            .iter()
+            .flat_map(|(fixed, free)| edge_values.iter().map(|edges| (*fixed, *free, *edges)))
+            .collect())
+    }
+
+    /// Runs all benchmarks of the suite and writes the results to the output file of the suite
+    ///
+    /// Each configuration is measured on `repetitions` graphs (generated with consecutive seeds),
+    /// the results contain one `BenchmarkStats` per configuration and solver that summarizes all measurements.
+    /// Every result is flushed to the output file immediately, so an interrupted run loses at most the current configuration.
+    /// When resuming, the returned results also contain the ones that were already present.
+    pub fn run(&self) -> Result<Vec<BenchmarkStats>, Error> {
+        let configurations = self.configurations()?;
+        if self.suite.repetitions == 0 {
+            return Err(Error::ValueError(
+                "The number of repetitions must be positive".to_string(),
+            ));
+        }
+        let base_seed = match (self.suite.seed, self.suite.resume) {
+            (Some(seed), _) => seed,
+            (None, false) => rand::thread_rng().gen(),
+            (None, true) => {
+                return Err(Error::ValueError(
+                    "A benchmark suite can only be resumed if it has a seed".to_string(),
+                ))
+            }
+        };
+
+        let is_resumed = self.suite.resume && fs::metadata(&self.suite.output).is_ok();
+        let mut benchmark_stats = if is_resumed {
+            read_json_lines(&self.suite.output)?
+        } else {
+            Vec::new()
+        };
+        println!(
+            "----- Run benchmark suite '{}' ({} configurations, {} repetitions, {} solvers, {} results present) --------------------------------------------------",
+            self.suite.name,
+            configurations.len(),
+            self.suite.repetitions,
+            self.suite.solvers.len(),
+            benchmark_stats.len()
+        );
+
+        let mut file = if is_resumed {
+            rewrite_json_lines(&benchmark_stats, &self.suite.output)?;
+            OpenOptions::new()
+                .append(true)
+                .open(&self.suite.output)
+                .map_err(Error::with_path(&self.suite.output))?
+        } else {
+            File::create(&self.suite.output).map_err(Error::with_path(&self.suite.output))?
+        };
+
+        for (configuration_index, configuration) in configurations.iter().enumerate() {
+            let first_seed = base_seed.wrapping_add((configuration_index * self.suite.repetitions) as u64);
+            let seeds: Vec<u64> = (0..self.suite.repetitions as u64)
+                .map(|repetition| first_seed.wrapping_add(repetition))
+                .collect();
+
+            let missing_solvers: Vec<Solver> = self
+                .suite
+                .solvers
+                .iter()
+                .filter(|solver| {
+                    !benchmark_stats
+                        .iter()
+                        .any(|benchmark| self.is_result_of(benchmark, *configuration, **solver, &seeds))
+                })
+                .copied()
+                .collect();
+            if missing_solvers.is_empty() {
+                continue;
+            }
+
+            for benchmark in self.run_configuration(*configuration, &seeds, &missing_solvers)? {
+                writeln!(file, "{}", serde_json::to_string(&benchmark)?)?;
+                file.flush()?;
+                benchmark_stats.push(benchmark);
+            }
+        }
+
+        if let Some(json_array_output) = &self.suite.json_array_output {
+            write_json_array(&benchmark_stats, json_array_output)?;
+        }
+        if let Some(chart_output) = &self.suite.chart_output {
+            let chart_axis = self.suite.chart_axis.unwrap_or_else(|| self.suite.varied_parameter());
+            LineChart::timing_chart(&benchmark_stats, chart_axis).write_svg(chart_output)?;
+        }
+
+        Ok(benchmark_stats)
+    }
+}
+
+// PRIVATE METHODS
+impl BenchmarkRunner {
+    /// Checks, if a result was measured for the configuration and solver on the graphs with the given seeds
+    fn is_result_of(
+        &self,
+        benchmark: &BenchmarkStats,
+        (number_of_fixed_nodes, number_of_free_nodes, number_of_edges): (usize, usize, usize),
+        solver: Solver,
+        seeds: &[u64],
+    ) -> bool {
+        benchmark.generator == self.suite.generator.to_string()
+            && benchmark.solver == solver.to_string()
+            && benchmark.number_of_fixed_nodes == number_of_fixed_nodes
+            && benchmark.number_of_free_nodes == number_of_free_nodes
+            && benchmark.number_of_edges
+                == self
+                    .suite
+                    .generator
+                    .number_of_generated_edges(number_of_free_nodes, number_of_edges)
+            && benchmark.seeds == seeds
+    }
+
+    /// Generates one graph per seed, runs the solvers on each of them and summarizes the measurements per solver
+    fn run_configuration(
+        &self,
+        (number_of_fixed_nodes, number_of_free_nodes, number_of_edges): (usize, usize, usize),
+        seeds: &[u64],
+        solvers: &[Solver],
+    ) -> Result<Vec<BenchmarkStats>, Error> {
+        let mut samples: Vec<Vec<Sample>> = solvers.iter().map(|_| Vec::new()).collect();
+        let mut graph_sizes = (number_of_fixed_nodes, number_of_free_nodes, number_of_edges);
+        let mut memory_usages = (0, 0);
+
+        for seed in seeds {
+            print!(
+                "Generating {} graph with {} fixed nodes, {} free nodes and {} edges (seed {})...",
+                self.suite.generator, number_of_fixed_nodes, number_of_free_nodes, number_of_edges, seed
+            );
+            io::stdout().flush()?;
+            let mut memory_tracker = MemoryTracker::start();
+            let begin = Instant::now();
+            let graph = self.suite.generator.generate(
+                number_of_fixed_nodes,
+                number_of_free_nodes,
+                number_of_edges,
+                &mut StdRng::seed_from_u64(*seed),
+            )?;
+            let loading_elapsed = begin.elapsed();
+            let loading_memory = memory_tracker.finish_phase();
+            println!(" done! ({} ms)", loading_elapsed.as_millis());
+
+            let compact_graph = CompactGraph::from_graph(&graph);
+            graph_sizes = (graph.number_of_fixed_nodes(), graph.number_of_free_nodes(), graph.number_of_edges());
+            memory_usages = (graph.memory_usage(), compact_graph.memory_usage());
+
+            for (solver, solver_samples) in solvers.iter().zip(&mut samples) {
+                print!("Running {solver}...");
+                io::stdout().flush()?;
+                let mut sample = self.run_solver(&graph, &compact_graph, *solver, *seed)?;
+                sample.loading_ns = loading_elapsed.as_nanos() as u64;
+                sample.loading_memory = loading_memory;
+                println!(
+                    " done! ({} ms)",
+                    (sample.reduction_ns + sample.ordering_ns) / 1_000_000
+                );
+                solver_samples.push(sample);
+            }
+        }
+
+        let (number_of_fixed_nodes, number_of_free_nodes, number_of_edges) = graph_sizes;
+        let (graph_memory_bytes, compact_graph_memory_bytes) = memory_usages;
+        Ok(solvers
+            .iter()
+            .zip(samples)
+            .map(|(solver, solver_samples)| {
+                let summarize = |value: fn(&Sample) -> u64| {
+                    SampleSummary::from_samples(solver_samples.iter().map(value).collect())
+                };
+                let loading_ns = summarize(|sample| sample.loading_ns);
+                let reduction_ns = summarize(|sample| sample.reduction_ns);
+                let ordering_ns = summarize(|sample| sample.ordering_ns);
+                let number_of_crossings = solver_samples
+                    .iter()
+                    .map(|sample| sample.number_of_crossings.map(|number_of_crossings| number_of_crossings as u64))
+                    .collect::<Option<Vec<u64>>>()
+                    .map(SampleSummary::from_samples);
+                let memory = |phase_memory: fn(&Sample) -> PhaseMemory| {
+                    solver_samples
+                        .iter()
+                        .map(phase_memory)
+                        .reduce(PhaseMemory::max)
+                };
+
+                BenchmarkStats {
+                    number_of_fixed_nodes,
+                    number_of_free_nodes,
+                    number_of_edges,
+                    loading_elapsed: loading_ns.mean_millis(),
+                    reduction_elapsed: reduction_ns.mean_millis(),
+                    ordering_elapsed: ordering_ns.mean_millis(),
+                    graph_memory_bytes,
+                    compact_graph_memory_bytes,
+                    generator: self.suite.generator.to_string(),
+                    solver: solver.to_string(),
+                    repetitions: seeds.len(),
+                    seeds: seeds.to_vec(),
+                    loading_ns: Some(loading_ns),
+                    reduction_ns: Some(reduction_ns),
+                    ordering_ns: Some(ordering_ns),
+                    number_of_crossings,
+                    loading_memory: memory(|sample| sample.loading_memory),
+                    reduction_memory: memory(|sample| sample.reduction_memory),
+                    ordering_memory: memory(|sample| sample.ordering_memory),
+                }
+            })
+            .collect())
+    }
+
+    /// Runs a solver on a graph, the loading time and memory of the sample are left empty
+    ///
+    /// The metaheuristics construct their data structures internally, so their reduction time and memory are included in the ordering phase.
+    fn run_solver(
+        &self,
+        graph: &Graph,
+        compact_graph: &CompactGraph,
+        solver: Solver,
+        seed: u64,
+    ) -> Result<Sample, Error> {
+        let time_limit = Duration::from_millis(self.suite.time_limit);
+
+        // The memory tracker reads and writes files in /proc, so it is only used outside of the timed sections
+        let mut memory_tracker = MemoryTracker::start();
+        let (reduction_elapsed, reduction_memory, ordering_elapsed, ordering) = match solver {
+            Solver::Fas { method, by_components } => {
+                let begin = Instant::now();
+                let penalty_digraph = PenaltyDigraph::from_compact_graph(compact_graph);
+                let reduction_elapsed = begin.elapsed();
+                let reduction_memory = memory_tracker.finish_phase();
+
+                let begin = Instant::now();
+                let ordering = if by_components {
+                    penalty_digraph.compute_fas_by_components(method, self.suite.exact_threshold)
+                } else {
+                    penalty_digraph.compute_fas(method)
+                };
+                let ordering_elapsed = begin.elapsed();
+                let ordering = ordering
+                    .into_iter()
+                    .map(|node| node + graph.number_of_fixed_nodes())
+                    .collect();
+                (reduction_elapsed, reduction_memory, ordering_elapsed, ordering)
+            }
+            Solver::SimulatedAnnealing => {
+                let simulated_annealing = SimulatedAnnealing {
+                    time_limit,
+                    seed,
+                    ..Default::default()
+                };
+                let begin = Instant::now();
+                let result = simulated_annealing.run(graph)?;
+                (Duration::ZERO, PhaseMemory::default(), begin.elapsed(), result.ordering)
+            }
+            Solver::IteratedGreedy => {
+                let iterated_greedy = IteratedGreedy {
+                    time_limit,
+                    seed,
+                    ..Default::default()
+                };
+                let begin = Instant::now();
+                let result = iterated_greedy.run(graph)?;
+                (Duration::ZERO, PhaseMemory::default(), begin.elapsed(), result.ordering)
+            }
+        };
+        let ordering_memory = memory_tracker.finish_phase();
+
+        let number_of_crossings = if self.suite.compute_number_of_crossings {
+            Some(compact_graph.compute_number_of_crossings_for_ordering(&ordering)?)
+        } else {
+            None
+        };
+
+        Ok(Sample {
+            loading_ns: 0,
+            reduction_ns: reduction_elapsed.as_nanos() as u64,
+            ordering_ns: ordering_elapsed.as_nanos() as u64,
+            number_of_crossings,
+            loading_memory: PhaseMemory::default(),
+            reduction_memory,
+            ordering_memory,
+        })
+    }
+}
+
+impl BenchmarkSuite {
+    /// Returns the parameter that is varied by the suite, preferring the number of edges
+    pub fn varied_parameter(&self) -> ChartAxis {
+        let is_varied = |range: &ParameterRange| matches!(range, ParameterRange::Range { .. });
+
+        if is_varied(&self.number_of_edges) {
+            ChartAxis::NumberOfEdges
+        } else if self.couple_node_ranges && is_varied(&self.number_of_fixed_nodes) {
+            ChartAxis::NumberOfNodes
+        } else if is_varied(&self.number_of_fixed_nodes) {
+            ChartAxis::NumberOfFixedNodes
+        } else if is_varied(&self.number_of_free_nodes) {
+            ChartAxis::NumberOfFreeNodes
+        } else {
+            ChartAxis::NumberOfEdges
+        }
+    }
+}
+
+impl ParameterRange {
+    /// Returns all values of the range
+    pub fn values(&self) -> Result<Vec<usize>, Error> {
+        match self {
+            ParameterRange::Value(value) => Ok(vec![*value]),
+            ParameterRange::Range { step: 0, .. } => Err(Error::ValueError(
+                "The step of a parameter range must be positive".to_string(),
+            )),
+            ParameterRange::Range { from, to, step } => Ok((*from..=*to).step_by(*step).collect()),
+        }
+    }
+}
+
+impl GeneratorKind {
+    /// Generates a graph with the given parameters
+    pub fn generate(
+        &self,
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        number_of_edges: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Graph, Error> {
+        let degree = GeneratorKind::degree(number_of_free_nodes, number_of_edges);
+
+        match self {
+            GeneratorKind::Random => GraphBuilder::build_random_graph_with_rng(
+                number_of_fixed_nodes,
+                number_of_free_nodes,
+                number_of_edges,
+                rng,
+            ),
+            GeneratorKind::PowerLaw { exponent } => GraphBuilder::build_power_law_graph(
+                number_of_fixed_nodes,
+                number_of_free_nodes,
+                number_of_edges,
+                *exponent,
+                rng,
+            ),
+            GeneratorKind::FixedDegree => {
+                GraphBuilder::build_graph_with_fixed_degree(number_of_fixed_nodes, number_of_free_nodes, degree, rng)
+            }
+            GeneratorKind::Geometric { spread } => GraphBuilder::build_geometric_graph(
+                number_of_fixed_nodes,
+                number_of_free_nodes,
+                degree,
+                *spread,
+                rng,
+            ),
+        }
+    }
+
+    /// Returns the number of edges of the graphs generated with the given parameters
+    ///
+    /// The generators with a fixed degree round the number of edges down to a multiple of the number of free nodes.
+    pub fn number_of_generated_edges(&self, number_of_free_nodes: usize, number_of_edges: usize) -> usize {
+        match self {
+            GeneratorKind::Random | GeneratorKind::PowerLaw { .. } => number_of_edges,
+            GeneratorKind::FixedDegree | GeneratorKind::Geometric { .. } => {
+                GeneratorKind::degree(number_of_free_nodes, number_of_edges) * number_of_free_nodes
+            }
+        }
+    }
+
+    fn degree(number_of_free_nodes: usize, number_of_edges: usize) -> usize {
+        number_of_edges.checked_div(number_of_free_nodes).unwrap_or(0)
+    }
+}
+
+impl fmt::Display for GeneratorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorKind::Random => write!(f, "random"),
+            GeneratorKind::PowerLaw { exponent } => write!(f, "power_law({exponent})"),
+            GeneratorKind::FixedDegree => write!(f, "fixed_degree"),
+            GeneratorKind::Geometric { spread } => write!(f, "geometric({spread})"),
+        }
+    }
+}
+
+impl fmt::Display for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Solver::Fas { method, by_components: false } => write!(f, "{method}"),
+            Solver::Fas { method, by_components: true } => write!(f, "{method}_by_components"),
+            Solver::SimulatedAnnealing => write!(f, "simulated_annealing"),
+            Solver::IteratedGreedy => write!(f, "iterated_greedy"),
+        }
+    }
+}
+
+impl FromStr for Solver {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "simulated_annealing" => Ok(Solver::SimulatedAnnealing),
+            "iterated_greedy" => Ok(Solver::IteratedGreedy),
+            _ => match name.strip_suffix("_by_components") {
+                Some(method) => Ok(Solver::Fas {
+                    method: method.parse()?,
+                    by_components: true,
+                }),
+                None => Ok(Solver::Fas {
+                    method: name.parse()?,
+                    by_components: false,
+                }),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for Solver {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<Solver> for String {
+    fn from(solver: Solver) -> Self {
+        solver.to_string()
+    }
+}
+
+/// Reads a JSON Lines file of `BenchmarkStats`
+///
+/// A last line that can not be parsed and is not terminated by a newline is the remainder of an interrupted run and is skipped.
+pub fn read_json_lines(filename: &str) -> Result<Vec<BenchmarkStats>, Error> {
+    let content = fs::read_to_string(filename).map_err(Error::with_path(filename))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut benchmark_stats = Vec::new();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(benchmark) => benchmark_stats.push(benchmark),
+            Err(_) if line_index + 1 == lines.len() && !content.ends_with('\n') => break,
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    Ok(benchmark_stats)
+}
+
+/// Replaces a JSON Lines file by the given results, which drops a line that was truncated by an interrupted run
+///
+/// The results are written to a temporary file in the same directory first, which is then renamed,
+/// so the present results are not lost if the rewrite is interrupted.
+fn rewrite_json_lines(benchmark_stats: &[BenchmarkStats], filename: &str) -> Result<(), Error> {
+    let temporary_filename = format!("{filename}.tmp");
+    let mut writer = BufWriter::new(File::create(&temporary_filename).map_err(Error::with_path(&temporary_filename))?);
+    for benchmark in benchmark_stats {
+        writeln!(writer, "{}", serde_json::to_string(benchmark)?)?;
+    }
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    drop(writer);
+
+    fs::rename(&temporary_filename, filename)?;
+    Ok(())
+}
+
+/// Converts a JSON Lines file of `BenchmarkStats` into a file with a JSON array of them
+pub fn convert_json_lines_to_array(json_lines_filename: &str, json_array_filename: &str) -> Result<(), Error> {
+    write_json_array(&read_json_lines(json_lines_filename)?, json_array_filename)
+}
+
+/// Writes the results as a pretty-printed JSON array
+fn write_json_array(benchmark_stats: &[BenchmarkStats], filename: &str) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(filename).map_err(Error::with_path(filename))?);
+    serde_json::to_writer_pretty(&mut writer, benchmark_stats)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn default_repetitions() -> usize {
+    1
+}
+
+fn default_solvers() -> Vec<Solver> {
+    vec![Solver::Fas {
+        method: FasMethod::SortFas,
+        by_components: false,
+    }]
+}
+
+fn default_time_limit() -> u64 {
+    1_000
+}
@@ -1,15 +1,153 @@
+use std::{fmt, ops::Range};
+
+use crate::verify::VerificationReport;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Could not resolve index")]
-    IndexError(String),
+    #[error("Index {index} is out of bounds, expected an index in {}..{}", range.start, range.end)]
+    IndexError { index: usize, range: Range<usize> },
 
-    #[error("Invalid value supplied")]
+    #[error("Invalid value supplied: {0}")]
     ValueError(String),
 
-    #[error("Something is wrong with the specified filepath")]
+    #[error("Something is wrong with the specified filepath: {0}")]
     FileError(#[from] std::io::Error),
 
-    #[error("Something is wrong with the specified filepath")]
-    ParseError(String),
-}
\ No newline at end of file
+    #[error("Could not access the file '{path}': {source}")]
+    PathError {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("Could not parse line {line}, column {column}: {kind}")]
+    ParseError {
+        line: usize,
+        column: usize,
+        kind: ParseErrorKind,
+    },
+
+    #[error("Invalid ordering: {}", describe_ordering_problems(missing, duplicates, invalid))]
+    InvalidOrdering {
+        missing: Vec<usize>,
+        duplicates: Vec<usize>,
+        invalid: Vec<usize>,
+    },
+
+    #[error("Invalid solution file {filename}: {report}")]
+    InvalidSolution {
+        filename: String,
+        report: Box<VerificationReport>,
+    },
+
+    #[error("The ordering constraints can not be satisfied: {0}")]
+    InfeasibleConstraints(String),
+
+    #[error("Could not convert to or from JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// Reason why a line of an input file could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The file does not contain a p line
+    MissingPLine,
+    /// A line has the wrong number of words
+    WrongNumberOfWords { expected: String, found: usize },
+    /// A word that should be a number is not
+    InvalidNumber(String),
+    /// The p line announces a different number of edges than the file contains
+    WrongNumberOfEdges { expected: usize, found: usize },
+    /// The edge can not be added to the graph
+    InvalidEdge(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::MissingPLine => write!(f, "Could not find a valid p line in the file"),
+            ParseErrorKind::WrongNumberOfWords { expected, found } => {
+                write!(f, "Expected {expected} words, but found {found}")
+            }
+            ParseErrorKind::InvalidNumber(word) => write!(f, "'{word}' is not a valid number"),
+            ParseErrorKind::WrongNumberOfEdges { expected, found } => write!(
+                f,
+                "{expected} edges were expected, but {found} were actually found"
+            ),
+            ParseErrorKind::InvalidEdge(reason) => write!(f, "Invalid edge ({reason})"),
+        }
+    }
+}
+
+impl Error {
+    /// Returns a function that wraps an I/O error on the given file into an `Error::PathError`
+    pub(crate) fn with_path(path: &str) -> impl FnOnce(std::io::Error) -> Error + '_ {
+        move |source| Error::PathError {
+            path: path.to_string(),
+            source,
+        }
+    }
+
+    /// Checks, if an index lies in the given range
+    pub(crate) fn check_index(index: usize, range: Range<usize>) -> Result<(), Error> {
+        if range.contains(&index) {
+            Ok(())
+        } else {
+            Err(Error::IndexError { index, range })
+        }
+    }
+
+    /// Checks, if an ordering contains every node of the given range exactly once
+    ///
+    /// Returns `Error::InvalidOrdering` with the missing, duplicate and invalid nodes (each sorted) otherwise.
+    pub(crate) fn check_ordering(ordering: &[usize], nodes: Range<usize>) -> Result<(), Error> {
+        let mut occurrences = vec![0usize; nodes.len()];
+        let mut invalid = Vec::new();
+        for node in ordering {
+            if nodes.contains(node) {
+                occurrences[node - nodes.start] += 1;
+            } else {
+                invalid.push(*node);
+            }
+        }
+        if invalid.is_empty() && occurrences.iter().all(|count| *count == 1) {
+            return Ok(());
+        }
+        invalid.sort_unstable();
+        invalid.dedup();
+
+        let nodes_with_occurrences = |predicate: fn(usize) -> bool| -> Vec<usize> {
+            nodes
+                .clone()
+                .filter(|node| predicate(occurrences[node - nodes.start]))
+                .collect()
+        };
+
+        Err(Error::InvalidOrdering {
+            missing: nodes_with_occurrences(|count| count == 0),
+            duplicates: nodes_with_occurrences(|count| count > 1),
+            invalid,
+        })
+    }
+}
+
+/// Summarizes the problems of an invalid ordering, listing at most 10 nodes per problem
+fn describe_ordering_problems(missing: &[usize], duplicates: &[usize], invalid: &[usize]) -> String {
+    let problems = [("missing", missing), ("duplicate", duplicates), ("invalid", invalid)];
+
+    problems
+        .iter()
+        .filter(|(_, nodes)| !nodes.is_empty())
+        .map(|(description, nodes)| format!("{} {description} ({})", nodes.len(), list_nodes(nodes)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Lists at most 10 nodes (or labels), separated by commas
+pub(crate) fn list_nodes(nodes: &[usize]) -> String {
+    let mut listed_nodes: Vec<String> = nodes.iter().take(10).map(usize::to_string).collect();
+    if nodes.len() > 10 {
+        listed_nodes.push("...".to_string());
+    }
+    listed_nodes.join(", ")
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use crate::{
+    error::Error,
+    graph::{penalty_digraph::PenaltyDigraph, Graph},
+};
+
+const NODE_SPACING: f64 = 48.0;
+const NODE_RADIUS: f64 = 14.0;
+const MARGIN: f64 = 40.0;
+const FIXED_LAYER_Y: f64 = 80.0;
+const FREE_LAYER_Y: f64 = 300.0;
+
+/// Edge of a two-layer drawing as `(fixed_node_index, free_node_index)`
+type Edge = (usize, usize);
+
+/// Returns all pairs of edges that cross if the free nodes are ordered as given
+///
+/// Edges that share a node never cross. The pairs are found by comparing all edges with each other,
+/// so this is only meant for small graphs.
+pub fn crossing_edge_pairs(graph: &Graph, ordering: &[usize]) -> Result<Vec<(Edge, Edge)>, Error> {
+    let positions = node_positions(graph, ordering)?;
+    let edges: Vec<Edge> = graph.edges().collect();
+
+    let mut pairs = Vec::new();
+    for (edge_index, (fixed1, free1)) in edges.iter().enumerate() {
+        for (fixed2, free2) in &edges[edge_index + 1..] {
+            let fixed_order = positions[*fixed1].cmp(&positions[*fixed2]);
+            let free_order = positions[*free1].cmp(&positions[*free2]);
+            if fixed_order.is_ne() && free_order.is_ne() && fixed_order != free_order {
+                pairs.push(((*fixed1, *free1), (*fixed2, *free2)));
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
+/// Renders the graph as a two-layer SVG drawing with the fixed layer on top and the free nodes ordered as given
+///
+/// Edges involved in a crossing are drawn in red and every crossing is marked, heavier edges are drawn thicker.
+/// Nodes are labelled with their 1-based labels of the PACE format.
+pub fn graph_to_svg(graph: &Graph, ordering: &[usize]) -> Result<String, Error> {
+    let positions = node_positions(graph, ordering)?;
+    let crossing_pairs = crossing_edge_pairs(graph, ordering)?;
+    let crossing_edges: HashSet<Edge> = crossing_pairs.iter().flat_map(|(edge1, edge2)| [*edge1, *edge2]).collect();
+    let number_of_crossings = graph.compute_number_of_crossings_for_ordering(ordering)?;
+
+    let layer_size = graph.number_of_fixed_nodes().max(graph.number_of_free_nodes());
+    let width = 2.0 * MARGIN + NODE_SPACING * layer_size.saturating_sub(1) as f64;
+    let height = FREE_LAYER_Y + NODE_RADIUS + MARGIN;
+    // Both layers are centered
+    let fixed_offset = MARGIN + NODE_SPACING * (layer_size - graph.number_of_fixed_nodes()) as f64 / 2.0;
+    let free_offset = MARGIN + NODE_SPACING * (layer_size - graph.number_of_free_nodes()) as f64 / 2.0;
+    let x = |node: usize| {
+        let offset = if node < graph.number_of_fixed_nodes() { fixed_offset } else { free_offset };
+        offset + NODE_SPACING * positions[node] as f64
+    };
+
+    let mut svg = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">"#
+        ),
+        format!(r#"<rect width="{width}" height="{height}" fill="white"/>"#),
+        format!(
+            r#"<text x="{MARGIN}" y="30" font-size="16">{number_of_crossings} crossings ({} crossing edge pairs)</text>"#,
+            crossing_pairs.len()
+        ),
+    ];
+
+    for (fixed, free, weight) in graph.weighted_edges() {
+        let color = if crossing_edges.contains(&(fixed, free)) { "#d62728" } else { "#7f7f7f" };
+        svg.push(format!(
+            r#"<line x1="{:.1}" y1="{FIXED_LAYER_Y}" x2="{:.1}" y2="{FREE_LAYER_Y}" stroke="{color}" stroke-width="{}"><title>{} - {} (weight {weight})</title></line>"#,
+            x(fixed),
+            x(free),
+            1.0 + f64::from(weight.min(5)) / 2.0,
+            fixed + 1,
+            free + 1
+        ));
+    }
+
+    for ((fixed1, free1), (fixed2, free2)) in &crossing_pairs {
+        // Both edges are straight lines between the layers, so they cross where their x-coordinates are equal
+        let (start1, end1, start2, end2) = (x(*fixed1), x(*free1), x(*fixed2), x(*free2));
+        let t = (start2 - start1) / ((end1 - start1) - (end2 - start2));
+        svg.push(format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="3" fill="#d62728"/>"##,
+            start1 + t * (end1 - start1),
+            FIXED_LAYER_Y + t * (FREE_LAYER_Y - FIXED_LAYER_Y)
+        ));
+    }
+
+    for node in 0..graph.number_of_nodes() {
+        let y = if node < graph.number_of_fixed_nodes() { FIXED_LAYER_Y } else { FREE_LAYER_Y };
+        svg.push(format!(
+            r#"<circle cx="{:.1}" cy="{y}" r="{NODE_RADIUS}" fill="white" stroke="black"/>"#,
+            x(node)
+        ));
+        svg.push(format!(
+            r#"<text x="{:.1}" y="{}" text-anchor="middle">{}</text>"#,
+            x(node),
+            y + 4.0,
+            node + 1
+        ));
+    }
+
+    svg.push("</svg>".to_string());
+    Ok(svg.join("\n") + "\n")
+}
+
+/// Exports the graph in the Graphviz DOT format, with both layers in their order and crossing edges in red
+///
+/// Nodes are labelled with their 1-based labels of the PACE format, edges with a weight other than 1 are labelled with it.
+pub fn graph_to_dot(graph: &Graph, ordering: &[usize]) -> Result<String, Error> {
+    let crossing_pairs = crossing_edge_pairs(graph, ordering)?;
+    let crossing_edges: HashSet<Edge> = crossing_pairs.iter().flat_map(|(edge1, edge2)| [*edge1, *edge2]).collect();
+    let labels = |nodes: &[usize]| -> Vec<String> { nodes.iter().map(|node| (node + 1).to_string()).collect() };
+
+    let mut dot = vec![
+        "graph ocm {".to_string(),
+        format!("    label=\"{} crossing edge pairs\";", crossing_pairs.len()),
+        "    node [shape=circle];".to_string(),
+    ];
+    dot.extend(ordered_rank("fixed", &labels(&graph.fixed_node_ordering()), "--"));
+    dot.extend(ordered_rank("free", &labels(ordering), "--"));
+
+    for (fixed, free, weight) in graph.weighted_edges() {
+        let mut attributes = Vec::new();
+        if crossing_edges.contains(&(fixed, free)) {
+            attributes.push("color=red".to_string());
+        }
+        if weight != 1 {
+            attributes.push(format!("label=\"{weight}\""));
+        }
+        dot.push(format!("    {} -- {}{};", fixed + 1, free + 1, format_attributes(&attributes)));
+    }
+
+    dot.push("}".to_string());
+    Ok(dot.join("\n") + "\n")
+}
+
+/// Exports the penalty digraph in the Graphviz DOT format, with the nodes in the given order
+///
+/// The feedback arcs of the ordering (which point backwards) are drawn bold and red, edges forced by constraints dashed.
+/// Nodes are labelled with their indices in the digraph, i.e. node `i` is the free node `number_of_fixed_nodes + i`.
+pub fn penalty_digraph_to_dot(penalty_digraph: &PenaltyDigraph, ordering: &[usize]) -> Result<String, Error> {
+    let feedback_arcs = penalty_digraph.feedback_arcs(ordering)?;
+    let feedback_weight: usize = feedback_arcs.iter().map(|(_, _, weight)| weight).sum();
+    let feedback_arcs: HashSet<(usize, usize)> = feedback_arcs.iter().map(|(u, v, _)| (*u, *v)).collect();
+    let labels: Vec<String> = ordering.iter().map(usize::to_string).collect();
+
+    let mut dot = vec![
+        "digraph penalty_digraph {".to_string(),
+        format!(
+            "    label=\"{} feedback arcs with weight {feedback_weight}\";",
+            feedback_arcs.len()
+        ),
+        "    node [shape=circle];".to_string(),
+    ];
+    dot.extend(ordered_rank("ordering", &labels, "->"));
+
+    for (u, v, weight) in penalty_digraph.edges() {
+        let mut attributes = vec![format!("label=\"{weight}\""), "constraint=false".to_string()];
+        if feedback_arcs.contains(&(u, v)) {
+            attributes.push("color=red".to_string());
+            attributes.push("penwidth=2".to_string());
+        }
+        if penalty_digraph.is_forced_edge(u, v) {
+            attributes.push("style=dashed".to_string());
+        }
+        dot.push(format!("    {u} -> {v}{};", format_attributes(&attributes)));
+    }
+
+    dot.push("}".to_string());
+    Ok(dot.join("\n") + "\n")
+}
+
+/// Returns the position of every node in its layer, with the free nodes ordered as given
+fn node_positions(graph: &Graph, ordering: &[usize]) -> Result<Vec<usize>, Error> {
+    Error::check_ordering(ordering, graph.free_nodes())?;
+
+    let mut positions = vec![0; graph.number_of_nodes()];
+    for fixed_node_index in graph.fixed_nodes() {
+        positions[fixed_node_index] = graph.fixed_node_position(fixed_node_index)?;
+    }
+    for (position, free_node_index) in ordering.iter().enumerate() {
+        positions[*free_node_index] = position;
+    }
+
+    Ok(positions)
+}
+
+/// Returns the lines of a DOT subgraph that places the nodes in one rank, in the order given by invisible edges
+fn ordered_rank(name: &str, labels: &[String], edge_operator: &str) -> Vec<String> {
+    let mut lines = vec![format!("    subgraph {name} {{"), "        rank=same;".to_string()];
+    lines.extend(labels.iter().map(|label| format!("        {label};")));
+    if labels.len() > 1 {
+        lines.push(format!(
+            "        {} [style=invis];",
+            labels.join(&format!(" {edge_operator} "))
+        ));
+    }
+    lines.push("    }".to_string());
+
+    lines
+}
+
+fn format_attributes(attributes: &[String]) -> String {
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
@@ -1,9 +1,14 @@
+pub mod analysis;
+pub mod compact_graph;
+pub mod constraints;
 pub mod penalty_digraph;
 
-use std::{collections::{BTreeSet, HashMap, HashSet}, iter};
+use std::{collections::{BTreeMap, HashSet}, iter, mem, ops::Range};
 
 use crate::error::Error;
 
+use self::compact_graph::CompactGraph;
+
 
 
 /// Default representation of a graph for the OCM problem
@@ -13,18 +18,22 @@ use crate::error::Error;
 /// `number_of_fixed_nodes` represents the number of nodes in set B
 /// `number_of_nodes` represents the total number of nodes
 /// `number_of_edges` represents the total number of edges (not directed). Only between a node from set A and a note from set B are allowed.
-/// `adjacency_list` represents the edges, stored in an adjacency-list format (adjacency_list[0] are all neighbours of node 0)
+/// `adjacency_list` represents the edges, stored in an adjacency-list format (adjacency_list[0] are all neighbours of node 0).
+/// Each neighbour is mapped to the weight of the edge. A crossing between two edges costs the product of their weights.
 /// 
 /// The nodes are numbered in ascending order. The fixed nodes have indices between `0`(inclusive) and `number_of_fixed_nodes`(exclusive). 
 /// The free nodes have indices between `number_of_fixed_nodes`(inclusive) and `number_of_nodes`(exclusive).
 /// The normally assumed ordering of the free nodes is in ascending index order.
+/// `fixed_node_positions` represents the ordering of the fixed layer (`fixed_node_positions[i]` is the position of fixed node `i`).
+/// By default the fixed nodes are ordered in ascending index order as well.
 #[derive(Debug)]
 pub struct Graph {
     number_of_nodes: usize,
     number_of_fixed_nodes: usize,
     number_of_free_nodes: usize,
     number_of_edges: usize,
-    adjacency_list: Vec<BTreeSet<usize>>,
+    adjacency_list: Vec<BTreeMap<usize, u32>>,
+    fixed_node_positions: Vec<usize>,
 }
 // GETTERS ---------------------------------------------------------------------------------
 impl Graph {
@@ -55,8 +64,43 @@ impl Graph {
             number_of_fixed_nodes,
             number_of_free_nodes,
             number_of_edges: 0,
-            adjacency_list: iter::repeat(BTreeSet::new()).take(number_of_nodes).collect(),
+            adjacency_list: iter::repeat_n(BTreeMap::new(), number_of_nodes).collect(),
+            fixed_node_positions: (0..number_of_fixed_nodes).collect(),
+        }
+    }
+
+    /// Sets the ordering of the fixed layer
+    ///
+    /// `ordering[p]` is the fixed node at position `p`.
+    /// The ordering **must** contain all fixed nodes (and each exactly once), otherwise the function returns an error
+    pub fn set_fixed_node_ordering(&mut self, ordering: &[usize]) -> Result<(), Error> {
+        Error::check_ordering(ordering, self.fixed_nodes())?;
+
+        for (position, fixed_node_index) in ordering.iter().enumerate() {
+            self.fixed_node_positions[*fixed_node_index] = position;
         }
+
+        Ok(())
+    }
+
+    /// Returns the ordering of the fixed layer, the fixed node at position `p` is at index `p` of the result
+    pub fn fixed_node_ordering(&self) -> Vec<usize> {
+        let mut ordering = vec![0; self.number_of_fixed_nodes];
+        for (fixed_node_index, position) in self.fixed_node_positions.iter().enumerate() {
+            ordering[*position] = fixed_node_index;
+        }
+        ordering
+    }
+
+    /// Returns the position of a fixed node in the fixed layer
+    pub fn fixed_node_position(&self, fixed_node_index: usize) -> Result<usize, Error> {
+        self.fixed_node_positions
+            .get(fixed_node_index)
+            .copied()
+            .ok_or(Error::IndexError {
+                index: fixed_node_index,
+                range: self.fixed_nodes(),
+            })
     }
 
     /// Adds an edge between two nodes
@@ -67,8 +111,25 @@ impl Graph {
     /// 
     /// Returns Err(_) if an error occurs
     pub fn add_edge(&mut self, node_index1: usize, node_index2: usize) -> Result<bool, Error> {
-        if node_index1 >= self.number_of_nodes || node_index2 >= self.number_of_nodes {
-            return Err(Error::IndexError("Index out of bounds".to_string()));
+        self.add_weighted_edge(node_index1, node_index2, 1)
+    }
+
+    /// Adds an edge with a given weight (e.g. the multiplicity of bundled connections) between two nodes
+    ///
+    /// Returns Ok(true) if the edge was inserted successfully.
+    /// Returns Ok(false) if the edge already existed, the weight of an existing edge is not changed.
+    ///
+    /// Returns Err(_) if an error occurs, e.g. if the edge does not connect a fixed node with a free node
+    pub fn add_weighted_edge(&mut self, node_index1: usize, node_index2: usize, weight: u32) -> Result<bool, Error> {
+        Error::check_index(node_index1, 0..self.number_of_nodes)?;
+        Error::check_index(node_index2, 0..self.number_of_nodes)?;
+        if self.fixed_nodes().contains(&node_index1) == self.fixed_nodes().contains(&node_index2) {
+            return Err(Error::ValueError(format!(
+                "The edge between {node_index1} and {node_index2} does not connect a fixed node with a free node"
+            )));
+        }
+        if weight == 0 {
+            return Err(Error::ValueError("The weight of an edge must be positive".to_string()));
         }
 
         let neighbors1 = self
@@ -76,14 +137,16 @@ impl Graph {
             .get_mut(node_index1)
             .expect("fixed_node_index is valid, so it should be in bound");
 
-        let inserted_successfully1 = neighbors1.insert(node_index2);
+        let inserted_successfully1 = !neighbors1.contains_key(&node_index2);
+        neighbors1.entry(node_index2).or_insert(weight);
 
         let neighbors2 = self
             .adjacency_list
             .get_mut(node_index2)
             .expect("free_node_index is valid, so it should be in bound");
 
-        let inserted_successfully2 = neighbors2.insert(node_index1);
+        let inserted_successfully2 = !neighbors2.contains_key(&node_index1);
+        neighbors2.entry(node_index1).or_insert(weight);
 
         if inserted_successfully1 && inserted_successfully2 {
             self.number_of_edges += 1;
@@ -92,44 +155,182 @@ impl Graph {
         Ok(inserted_successfully1)
     }
 
+    /// Removes the edge between two nodes
+    ///
+    /// Returns Ok(true) if the edge was removed successfully.
+    /// Returns Ok(false) if the edge did not exist.
+    ///
+    /// Returns Err(_) if an error occurs
+    pub fn remove_edge(&mut self, node_index1: usize, node_index2: usize) -> Result<bool, Error> {
+        Error::check_index(node_index1, 0..self.number_of_nodes)?;
+        Error::check_index(node_index2, 0..self.number_of_nodes)?;
+
+        let removed_successfully1 = self.adjacency_list[node_index1].remove(&node_index2).is_some();
+        let removed_successfully2 = self.adjacency_list[node_index2].remove(&node_index1).is_some();
+
+        if removed_successfully1 && removed_successfully2 {
+            self.number_of_edges -= 1;
+        }
+
+        Ok(removed_successfully1)
+    }
+
+    /// Removes a free node together with all of its edges
+    ///
+    /// The free nodes after the removed one move down by one index, so that the free node indices stay contiguous.
+    pub fn remove_free_node(&mut self, free_node_index: usize) -> Result<(), Error> {
+        Error::check_index(free_node_index, self.free_nodes())?;
+
+        let neighbors = self.adjacency_list.remove(free_node_index);
+        self.number_of_edges -= neighbors.len();
+        self.number_of_free_nodes -= 1;
+        self.number_of_nodes -= 1;
+
+        for fixed_node_index in 0..self.number_of_fixed_nodes {
+            let adjacent_nodes = &mut self.adjacency_list[fixed_node_index];
+            if adjacent_nodes.last_key_value().is_some_and(|(last, _)| *last >= free_node_index) {
+                *adjacent_nodes = adjacent_nodes
+                    .iter()
+                    .filter(|(node_index, _)| **node_index != free_node_index)
+                    .map(|(node_index, weight)| {
+                        if *node_index > free_node_index {
+                            (node_index - 1, *weight)
+                        } else {
+                            (*node_index, *weight)
+                        }
+                    })
+                    .collect();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Constructs the subgraph induced by all fixed nodes and the given free nodes
+    ///
+    /// The fixed nodes keep their indices and their ordering. The free node `free_nodes[i]` gets the index `number_of_fixed_nodes + i` in the subgraph,
+    /// so `free_nodes` maps the free nodes of the subgraph back to this graph.
+    /// Each free node may be given at most once, otherwise the function returns an error
+    pub fn induced_subgraph(&self, free_nodes: &[usize]) -> Result<Graph, Error> {
+        let mut included_indices = HashSet::new();
+        for free_node_index in free_nodes {
+            Error::check_index(*free_node_index, self.free_nodes())?;
+            if !included_indices.insert(*free_node_index) {
+                return Err(Error::ValueError(
+                    "The free nodes of the subgraph contain duplicates".to_string(),
+                ));
+            }
+        }
+
+        let mut subgraph = Graph::new(self.number_of_fixed_nodes, free_nodes.len());
+        subgraph.fixed_node_positions.clone_from(&self.fixed_node_positions);
+        for (offset, free_node_index) in free_nodes.iter().enumerate() {
+            for (fixed_node_index, weight) in &self.adjacency_list[*free_node_index] {
+                subgraph.add_weighted_edge(*fixed_node_index, self.number_of_fixed_nodes + offset, *weight)?;
+            }
+        }
+
+        Ok(subgraph)
+    }
+
     /// Checks, if an edge between two nodes exists
     pub fn does_edge_exist(&self, index1: usize, index2: usize) -> Result<bool, Error> {
-        if index1 >= self.number_of_nodes || index2 >= self.number_of_nodes {
-            return Err(Error::IndexError("Index is out of bounds".to_string()));
-        }
+        Error::check_index(index1, 0..self.number_of_nodes)?;
+        Error::check_index(index2, 0..self.number_of_nodes)?;
 
         Ok(self
             .adjacency_list
             .get(index1)
             .expect("Index exists")
-            .contains(&index2))
+            .contains_key(&index2))
     }
 
-    /// Computes the number of crossings with all free nodes in ascending index order
-    pub fn compute_number_of_crossings_with_default_ordering(&self) -> Result<usize, Error> {
-        let mut number_of_crossings = 0;
-
-        for fixed_node_index1 in 0..self.number_of_fixed_nodes {
-            for fixed_node_index2 in (fixed_node_index1 + 1)..self.number_of_fixed_nodes {
-                for neighbor_index1 in self
-                    .adjacency_list
-                    .get(fixed_node_index1)
-                    .expect("Index must exist")
-                {
-                    for neighbor_index2 in self
-                        .adjacency_list
-                        .get(fixed_node_index2)
-                        .expect("Index must exist")
-                    {
-                        if neighbor_index2 < neighbor_index1 {
-                            number_of_crossings += 1;
-                        }
-                    }
-                }
+    /// Returns the indices of the fixed nodes
+    pub fn fixed_nodes(&self) -> Range<usize> {
+        0..self.number_of_fixed_nodes
+    }
+
+    /// Returns the indices of the free nodes
+    pub fn free_nodes(&self) -> Range<usize> {
+        self.number_of_fixed_nodes..self.number_of_nodes
+    }
+
+    /// Returns the neighbors of a node in ascending index order
+    pub fn neighbors(&self, index: usize) -> Result<impl Iterator<Item = usize> + '_, Error> {
+        let neighbors = self
+            .adjacency_list
+            .get(index)
+            .ok_or(Error::IndexError {
+                index,
+                range: 0..self.number_of_nodes,
+            })?;
+
+        Ok(neighbors.keys().copied())
+    }
+
+    /// Returns the number of neighbors of a node
+    pub fn degree(&self, index: usize) -> Result<usize, Error> {
+        self.adjacency_list
+            .get(index)
+            .map(BTreeMap::len)
+            .ok_or(Error::IndexError {
+                index,
+                range: 0..self.number_of_nodes,
+            })
+    }
+
+    /// Returns all edges as `(fixed_node_index, free_node_index)` pairs
+    ///
+    /// Every edge is listed exactly once, sorted by its fixed node first and by its free node second.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.weighted_edges()
+            .map(|(fixed_node_index, free_node_index, _)| (fixed_node_index, free_node_index))
+    }
+
+    /// Returns all edges as `(fixed_node_index, free_node_index, weight)` triples, sorted like `Graph::edges`
+    pub fn weighted_edges(&self) -> impl Iterator<Item = (usize, usize, u32)> + '_ {
+        self.fixed_nodes().flat_map(move |fixed_node_index| {
+            self.adjacency_list[fixed_node_index]
+                .iter()
+                .map(move |(free_node_index, weight)| (fixed_node_index, *free_node_index, *weight))
+        })
+    }
+
+    /// Returns the weight of the edge between two nodes, or `None` if the edge does not exist
+    pub fn edge_weight(&self, index1: usize, index2: usize) -> Result<Option<u32>, Error> {
+        Error::check_index(index1, 0..self.number_of_nodes)?;
+        Error::check_index(index2, 0..self.number_of_nodes)?;
+
+        Ok(self.adjacency_list[index1].get(&index2).copied())
+    }
+
+    /// Constructs a copy of the graph in which the free nodes are renamed
+    ///
+    /// `permutation[i]` is the new index of the free node with index `number_of_fixed_nodes + i`.
+    /// The permutation **must** contain every free node index exactly once, otherwise the function returns an error
+    pub fn relabel_free_nodes(&self, permutation: &[usize]) -> Result<Graph, Error> {
+        Error::check_ordering(permutation, self.free_nodes())?;
+
+        let mut graph = Graph::new(self.number_of_fixed_nodes, self.number_of_free_nodes);
+        graph.fixed_node_positions.clone_from(&self.fixed_node_positions);
+        for (offset, new_free_node_index) in permutation.iter().enumerate() {
+            for (fixed_node_index, weight) in self
+                .adjacency_list
+                .get(self.number_of_fixed_nodes + offset)
+                .expect("Index must exist")
+            {
+                graph.add_weighted_edge(*fixed_node_index, *new_free_node_index, *weight)?;
             }
         }
 
-        Ok(number_of_crossings)
+        Ok(graph)
+    }
+
+    /// Computes the number of crossings with all free nodes in ascending index order
+    ///
+    /// Like all crossing computations, this respects the ordering of the fixed layer.
+    pub fn compute_number_of_crossings_with_default_ordering(&self) -> Result<usize, Error> {
+        Ok(CompactGraph::from_graph(self).compute_number_of_crossings_with_default_ordering())
     }
 
     /// Computes the number of crossings for a specific ordering of the free nodes
@@ -137,53 +338,77 @@ impl Graph {
     /// The input ordering **must** contain all free nodes (and each exactly once), otherwise the function returns an error
     pub fn compute_number_of_crossings_for_ordering(
         &self,
-        ordering: &Vec<usize>,
+        ordering: &[usize],
     ) -> Result<usize, Error> {
-        if ordering.len() != self.number_of_free_nodes {
-            return Err(Error::ValueError(
-                "The ordering does not contain all free nodes".to_string(),
-            ));
-        }
-        let included_indices: HashSet<usize> = ordering.iter().cloned().collect();
-        if included_indices != (self.number_of_fixed_nodes..self.number_of_nodes).collect() {
-            return Err(Error::ValueError(
-                "The ordering does not contain all free nodes".to_string(),
+        CompactGraph::from_graph(self).compute_number_of_crossings_for_ordering(ordering)
+    }
+
+    /// Estimates the number of bytes used by this struct (including its heap allocations)
+    ///
+    /// Each `BTreeMap` with `k` elements needs at least `ceil(k / 11)` B-tree leaves,
+    /// each holding 11 keys, 11 weights, a parent pointer and two `u16` counters. Inner nodes are not counted.
+    pub fn memory_usage(&self) -> usize {
+        const KEYS_PER_LEAF: usize = 11;
+        const LEAF_SIZE: usize = KEYS_PER_LEAF * (mem::size_of::<usize>() + mem::size_of::<u32>())
+            + mem::size_of::<usize>()
+            + 2 * mem::size_of::<u16>();
+
+        let number_of_leaves: usize = self
+            .adjacency_list
+            .iter()
+            .map(|neighbors| neighbors.len().div_ceil(KEYS_PER_LEAF))
+            .sum();
+
+        mem::size_of::<Graph>()
+            + self.adjacency_list.capacity() * mem::size_of::<BTreeMap<usize, u32>>()
+            + self.fixed_node_positions.capacity() * mem::size_of::<usize>()
+            + number_of_leaves * LEAF_SIZE.next_multiple_of(mem::align_of::<usize>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_must_connect_a_fixed_node_with_a_free_node() {
+        let mut graph = Graph::new(2, 2);
+
+        for (node_index1, node_index2) in [(0, 1), (2, 3), (0, 0), (2, 2)] {
+            assert!(matches!(
+                graph.add_edge(node_index1, node_index2),
+                Err(Error::ValueError(_))
             ));
         }
+        assert_eq!(graph.number_of_edges(), 0);
+        assert_eq!(graph.compute_number_of_crossings_with_default_ordering().unwrap(), 0);
+    }
 
-        let mut positions = HashMap::new();
-        for (position, free_node_index) in ordering.iter().enumerate() {
-            positions.insert(*free_node_index, position);
-        }
+    #[test]
+    fn edges_are_listed_once_from_the_fixed_node() {
+        let mut graph = Graph::new(2, 2);
 
-        let mut number_of_crossings = 0;
-        for fixed_node_index1 in 0..self.number_of_fixed_nodes {
-            for fixed_node_index2 in (fixed_node_index1 + 1)..self.number_of_fixed_nodes {
-                for neighbor_index1 in self
-                    .adjacency_list
-                    .get(fixed_node_index1)
-                    .expect("Index must exist")
-                {
-                    for neighbor_index2 in self
-                        .adjacency_list
-                        .get(fixed_node_index2)
-                        .expect("Index must exist")
-                    {
-                        let position1 = positions
-                            .get(neighbor_index1)
-                            .expect("A position must have been found");
-                        let position2 = positions
-                            .get(neighbor_index2)
-                            .expect("A position must have been found");
-
-                        if position2 < position1 {
-                            number_of_crossings += 1;
-                        }
-                    }
-                }
-            }
+        assert!(graph.add_edge(0, 3).unwrap());
+        assert!(graph.add_edge(2, 1).unwrap());
+        assert!(!graph.add_edge(3, 0).unwrap());
+
+        assert_eq!(graph.number_of_edges(), 2);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), vec![(0, 3), (1, 2)]);
+        assert_eq!(graph.weighted_edges().collect::<Vec<_>>(), vec![(0, 3, 1), (1, 2, 1)]);
+        assert_eq!(graph.compute_number_of_crossings_with_default_ordering().unwrap(), 1);
+    }
+
+    #[test]
+    fn edges_match_the_number_of_edges() {
+        let mut graph = Graph::new(3, 3);
+        for (node_index1, node_index2) in [(0, 3), (4, 0), (1, 2), (5, 2), (3, 4), (2, 2)] {
+            let _ = graph.add_weighted_edge(node_index1, node_index2, 2);
         }
 
-        Ok(number_of_crossings)
+        assert_eq!(graph.edges().count(), graph.number_of_edges());
+        assert!(graph
+            .edges()
+            .all(|(fixed_node_index, free_node_index)| graph.fixed_nodes().contains(&fixed_node_index)
+                && graph.free_nodes().contains(&free_node_index)));
     }
 }
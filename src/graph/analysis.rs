@@ -0,0 +1,301 @@
+use std::{collections::BTreeMap, collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::{penalty_digraph::PenaltyDigraph, Graph};
+
+/// Structural properties of a `Graph` that help to choose a solver, see `Graph::analyze`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphAnalysis {
+    pub number_of_fixed_nodes: usize,
+    pub number_of_free_nodes: usize,
+    pub number_of_edges: usize,
+    pub fixed_degrees: DegreeDistribution,
+    pub free_degrees: DegreeDistribution,
+    pub number_of_isolated_fixed_nodes: usize,
+    pub number_of_isolated_free_nodes: usize,
+    /// Number of distinct (weighted) neighborhoods of the non-isolated free nodes
+    pub number_of_twin_classes: usize,
+    /// Number of free nodes in the largest class of free nodes with the same neighborhood
+    pub largest_twin_class: usize,
+    /// Fraction of the pairs of non-isolated free nodes whose neighbor intervals overlap, i.e. that can cross at all
+    pub interval_overlap_density: f64,
+    /// Number of connected components with at least one edge
+    pub number_of_connected_components: usize,
+    /// Number of groups of non-isolated free nodes with overlapping neighbor intervals,
+    /// which can be ordered independently of each other
+    pub number_of_independent_components: usize,
+    /// Number of strongly connected components of the penalty digraph per size
+    pub scc_sizes: BTreeMap<usize, usize>,
+}
+
+/// Distribution of the degrees of the nodes of one layer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DegreeDistribution {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+    /// Number of nodes per degree
+    pub histogram: BTreeMap<usize, usize>,
+}
+
+// PUBLIC METHODS
+impl Graph {
+    /// Computes the degree distributions, twins, neighbor-interval overlaps and components of the graph
+    ///
+    /// The neighbor interval of a free node spans the positions of its first and last neighbor in the fixed layer.
+    /// The strongly connected components are computed on the penalty digraph, which needs `O(n^2)` time and memory
+    /// in the number of free nodes `n`.
+    pub fn analyze(&self) -> GraphAnalysis {
+        let intervals: Vec<(usize, usize)> = self
+            .free_nodes()
+            .filter_map(|free_node_index| self.neighbor_interval(free_node_index))
+            .collect();
+        let (number_of_twin_classes, largest_twin_class) = self.twin_classes();
+
+        let mut scc_sizes = BTreeMap::new();
+        for component in PenaltyDigraph::from_graph(self).strongly_connected_components() {
+            *scc_sizes.entry(component.len()).or_default() += 1;
+        }
+
+        GraphAnalysis {
+            number_of_fixed_nodes: self.number_of_fixed_nodes,
+            number_of_free_nodes: self.number_of_free_nodes,
+            number_of_edges: self.number_of_edges,
+            fixed_degrees: self.degree_distribution(self.fixed_nodes()),
+            free_degrees: self.degree_distribution(self.free_nodes()),
+            number_of_isolated_fixed_nodes: self.fixed_nodes().filter(|node| self.adjacency_list[*node].is_empty()).count(),
+            number_of_isolated_free_nodes: self.free_nodes().filter(|node| self.adjacency_list[*node].is_empty()).count(),
+            number_of_twin_classes,
+            largest_twin_class,
+            interval_overlap_density: interval_overlap_density(&intervals),
+            number_of_connected_components: self.number_of_connected_components(),
+            number_of_independent_components: number_of_independent_components(intervals),
+            scc_sizes,
+        }
+    }
+}
+
+// PRIVATE METHODS
+impl Graph {
+    fn degree_distribution(&self, nodes: impl Iterator<Item = usize>) -> DegreeDistribution {
+        let degrees: Vec<usize> = nodes.map(|node| self.adjacency_list[node].len()).collect();
+        if degrees.is_empty() {
+            return DegreeDistribution::default();
+        }
+
+        let mut histogram = BTreeMap::new();
+        for degree in &degrees {
+            *histogram.entry(*degree).or_default() += 1;
+        }
+
+        DegreeDistribution {
+            min: *degrees.iter().min().expect("There is at least one degree"),
+            max: *degrees.iter().max().expect("There is at least one degree"),
+            mean: degrees.iter().sum::<usize>() as f64 / degrees.len() as f64,
+            histogram,
+        }
+    }
+
+    /// Returns the positions of the first and last neighbor of a free node in the fixed layer, or `None` if it is isolated
+    ///
+    /// `Graph::add_weighted_edge` only accepts edges between a fixed and a free node, so all neighbors are fixed nodes.
+    fn neighbor_interval(&self, free_node_index: usize) -> Option<(usize, usize)> {
+        let positions = self.adjacency_list[free_node_index]
+            .keys()
+            .map(|fixed_node_index| self.fixed_node_positions[*fixed_node_index]);
+
+        positions.fold(None, |interval, position| match interval {
+            None => Some((position, position)),
+            Some((first, last)) => Some((first.min(position), last.max(position))),
+        })
+    }
+
+    /// Returns the number of twin classes and the size of the largest one (among the non-isolated free nodes)
+    fn twin_classes(&self) -> (usize, usize) {
+        let mut class_sizes: HashMap<Vec<(usize, u32)>, usize> = HashMap::new();
+        for free_node_index in self.free_nodes() {
+            let neighborhood = &self.adjacency_list[free_node_index];
+            if !neighborhood.is_empty() {
+                let neighborhood = neighborhood.iter().map(|(node, weight)| (*node, *weight)).collect();
+                *class_sizes.entry(neighborhood).or_default() += 1;
+            }
+        }
+
+        (class_sizes.len(), class_sizes.values().copied().max().unwrap_or(0))
+    }
+
+    fn number_of_connected_components(&self) -> usize {
+        let mut is_visited = vec![false; self.number_of_nodes];
+        let mut number_of_components = 0;
+
+        for start in 0..self.number_of_nodes {
+            if is_visited[start] || self.adjacency_list[start].is_empty() {
+                continue;
+            }
+            number_of_components += 1;
+            is_visited[start] = true;
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for neighbor in self.adjacency_list[node].keys() {
+                    if !is_visited[*neighbor] {
+                        is_visited[*neighbor] = true;
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+        }
+
+        number_of_components
+    }
+}
+
+impl fmt::Display for GraphAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format_histogram = |histogram: &BTreeMap<usize, usize>| -> String {
+            let entries: Vec<String> = histogram.iter().map(|(value, count)| format!("{value}: {count}")).collect();
+            entries.join(", ")
+        };
+        let format_degrees = |degrees: &DegreeDistribution| {
+            format!(
+                "min {}, max {}, mean {:.2} (degree: nodes {})",
+                degrees.min,
+                degrees.max,
+                degrees.mean,
+                format_histogram(&degrees.histogram)
+            )
+        };
+
+        writeln!(
+            f,
+            "{} fixed nodes, {} free nodes and {} edges",
+            self.number_of_fixed_nodes, self.number_of_free_nodes, self.number_of_edges
+        )?;
+        writeln!(f, "Degrees of the fixed nodes: {}", format_degrees(&self.fixed_degrees))?;
+        writeln!(f, "Degrees of the free nodes: {}", format_degrees(&self.free_degrees))?;
+        writeln!(
+            f,
+            "Isolated nodes: {} fixed, {} free",
+            self.number_of_isolated_fixed_nodes, self.number_of_isolated_free_nodes
+        )?;
+        writeln!(
+            f,
+            "Twin classes: {} (largest with {} free nodes)",
+            self.number_of_twin_classes, self.largest_twin_class
+        )?;
+        writeln!(
+            f,
+            "Neighbor interval overlap density: {:.4}",
+            self.interval_overlap_density
+        )?;
+        writeln!(
+            f,
+            "Components: {} connected, {} independent",
+            self.number_of_connected_components, self.number_of_independent_components
+        )?;
+        write!(
+            f,
+            "Strongly connected components of the penalty digraph (size: count {})",
+            format_histogram(&self.scc_sizes)
+        )
+    }
+}
+
+/// Computes the fraction of pairs of intervals that overlap in more than one point
+///
+/// Two intervals do not overlap if one ends before or where the other one starts.
+/// This holds in both directions only for two equal intervals of a single point.
+fn interval_overlap_density(intervals: &[(usize, usize)]) -> f64 {
+    let number_of_intervals = intervals.len();
+    if number_of_intervals < 2 {
+        return 0.0;
+    }
+
+    let mut lasts: Vec<usize> = intervals.iter().map(|(_, last)| *last).collect();
+    lasts.sort_unstable();
+    // Ordered pairs (u, v) where u ends before or where v starts, including u = v for single points
+    let ordered_separated_pairs: usize = intervals
+        .iter()
+        .map(|(first, _)| lasts.partition_point(|last| last <= first))
+        .sum();
+
+    let mut single_points: HashMap<usize, usize> = HashMap::new();
+    for (first, last) in intervals {
+        if first == last {
+            *single_points.entry(*first).or_default() += 1;
+        }
+    }
+    let number_of_single_points: usize = single_points.values().sum();
+    let doubly_counted_pairs: usize = single_points.values().map(|count| count * (count - 1) / 2).sum();
+
+    let separated_pairs = ordered_separated_pairs - number_of_single_points - doubly_counted_pairs;
+    let number_of_pairs = number_of_intervals * (number_of_intervals - 1) / 2;
+
+    (number_of_pairs - separated_pairs) as f64 / number_of_pairs as f64
+}
+
+/// Counts the groups of intervals that are connected by overlaps (in more than one point)
+fn number_of_independent_components(mut intervals: Vec<(usize, usize)>) -> usize {
+    intervals.sort_unstable();
+
+    let mut number_of_components = 0;
+    let mut current_last = None;
+    for (first, last) in intervals {
+        match current_last {
+            Some(current) if first < current => current_last = Some(last.max(current)),
+            _ => {
+                number_of_components += 1;
+                current_last = Some(last);
+            }
+        }
+    }
+
+    number_of_components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The free nodes 3 and 4 are twins with the neighbors 0 and 1, the free node 5 is adjacent to 2 and 6 is isolated
+    fn graph() -> Graph {
+        let mut graph = Graph::new(3, 4);
+        for (fixed_node_index, free_node_index) in [(0, 3), (1, 3), (0, 4), (1, 4), (2, 5)] {
+            graph.add_edge(fixed_node_index, free_node_index).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn analysis_of_a_small_graph() {
+        let analysis = graph().analyze();
+
+        assert_eq!(analysis.number_of_edges, 5);
+        assert_eq!(analysis.fixed_degrees.histogram, BTreeMap::from([(1, 1), (2, 2)]));
+        assert_eq!((analysis.free_degrees.min, analysis.free_degrees.max), (0, 2));
+        assert_eq!(analysis.free_degrees.mean, 1.25);
+        assert_eq!(analysis.number_of_isolated_fixed_nodes, 0);
+        assert_eq!(analysis.number_of_isolated_free_nodes, 1);
+        assert_eq!((analysis.number_of_twin_classes, analysis.largest_twin_class), (2, 2));
+        assert_eq!(analysis.interval_overlap_density, 1.0 / 3.0);
+        assert_eq!(analysis.number_of_connected_components, 2);
+        assert_eq!(analysis.number_of_independent_components, 2);
+        assert_eq!(analysis.scc_sizes, BTreeMap::from([(1, 4)]));
+    }
+
+    #[test]
+    fn rejected_edges_within_one_layer_do_not_affect_the_analysis() {
+        let mut graph = graph();
+        assert!(graph.add_edge(3, 4).is_err());
+        assert!(graph.add_edge(0, 1).is_err());
+
+        assert_eq!(graph.analyze(), self::graph().analyze());
+    }
+
+    #[test]
+    fn overlap_density_ignores_intervals_that_only_touch() {
+        assert_eq!(interval_overlap_density(&[(0, 2), (2, 4), (1, 3)]), 2.0 / 3.0);
+        assert_eq!(interval_overlap_density(&[(1, 1), (1, 1)]), 0.0);
+        assert_eq!(number_of_independent_components(vec![(0, 2), (2, 4), (5, 5)]), 3);
+    }
+}
@@ -0,0 +1,393 @@
+use std::mem;
+
+use super::Graph;
+use crate::error::Error;
+
+
+/// Frozen representation of a `Graph` in compressed-sparse-row format
+///
+/// This struct stores the same bipartite graph as a `Graph`, but it can not be modified anymore.
+/// `neighbors` contains the sorted neighbors of all nodes one after another.
+/// `offsets` marks where the neighbors of each node start (the neighbors of node `i` are `neighbors[offsets[i]..offsets[i + 1]]`).
+///
+/// `weights` contains the weight of each of these edges at the same index, it is only stored if some edge has a weight other than 1.
+///
+/// Compared to the adjacency list of a `Graph`, this needs only 4 bytes per edge and side (plus the offsets),
+/// and the neighbors of a node are stored contiguously, which makes the crossing computations cache friendly.
+///
+/// The free nodes have the same indices as in the `Graph` the struct was built from.
+/// The fixed nodes are renumbered by their position in the fixed layer, i.e. fixed node `p` of a `CompactGraph`
+/// is the fixed node at position `p` of the `Graph`. This way all crossing computations only have to compare indices.
+#[derive(Debug)]
+pub struct CompactGraph {
+    number_of_nodes: usize,
+    number_of_fixed_nodes: usize,
+    number_of_free_nodes: usize,
+    number_of_edges: usize,
+    offsets: Vec<usize>,
+    neighbors: Vec<u32>,
+    weights: Option<Vec<u32>>,
+}
+
+// GETTERS
+impl CompactGraph {
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    pub fn number_of_fixed_nodes(&self) -> usize {
+        self.number_of_fixed_nodes
+    }
+
+    pub fn number_of_free_nodes(&self) -> usize {
+        self.number_of_free_nodes
+    }
+
+    pub fn number_of_edges(&self) -> usize {
+        self.number_of_edges
+    }
+}
+
+// CONSTRUCTORS
+impl CompactGraph {
+    /// Constructs a new `CompactGraph` with the same nodes and edges as the given `Graph`
+    pub fn from_graph(graph: &Graph) -> CompactGraph {
+        let mut offsets = Vec::with_capacity(graph.number_of_nodes + 1);
+        let mut neighbors = Vec::with_capacity(2 * graph.number_of_edges);
+        let mut weights = Vec::with_capacity(2 * graph.number_of_edges);
+
+        let to_u32 =
+            |node_index: usize| u32::try_from(node_index).expect("The graph has less than 2^32 nodes");
+
+        offsets.push(0);
+        for fixed_node_index in graph.fixed_node_ordering() {
+            for (free_node_index, weight) in &graph.adjacency_list[fixed_node_index] {
+                neighbors.push(to_u32(*free_node_index));
+                weights.push(*weight);
+            }
+            offsets.push(neighbors.len());
+        }
+        for adjacent_nodes in &graph.adjacency_list[graph.number_of_fixed_nodes..] {
+            let mut weighted_neighbors: Vec<(u32, u32)> = adjacent_nodes
+                .iter()
+                .map(|(fixed_node_index, weight)| {
+                    (to_u32(graph.fixed_node_positions[*fixed_node_index]), *weight)
+                })
+                .collect();
+            weighted_neighbors.sort_unstable();
+            for (fixed_node_position, weight) in weighted_neighbors {
+                neighbors.push(fixed_node_position);
+                weights.push(weight);
+            }
+            offsets.push(neighbors.len());
+        }
+        let weights = if weights.iter().all(|weight| *weight == 1) {
+            None
+        } else {
+            Some(weights)
+        };
+
+        CompactGraph {
+            number_of_nodes: graph.number_of_nodes,
+            number_of_fixed_nodes: graph.number_of_fixed_nodes,
+            number_of_free_nodes: graph.number_of_free_nodes,
+            number_of_edges: graph.number_of_edges,
+            offsets,
+            neighbors,
+            weights,
+        }
+    }
+}
+
+// PUBLIC METHODS
+impl CompactGraph {
+    /// Returns the sorted neighbors of a node
+    ///
+    /// Panics if the node does not exist
+    pub fn neighbors(&self, node_index: usize) -> &[u32] {
+        &self.neighbors[self.offsets[node_index]..self.offsets[node_index + 1]]
+    }
+
+    /// Returns the weights of the edges to the neighbors of a node (in the same order as `CompactGraph::neighbors`)
+    ///
+    /// Panics if the node does not exist
+    pub fn weights(&self, node_index: usize) -> impl Iterator<Item = usize> + Clone + '_ {
+        let range = self.offsets[node_index]..self.offsets[node_index + 1];
+        range.map(|edge| self.weight(edge))
+    }
+
+    /// Returns the number of neighbors of a node
+    ///
+    /// Panics if the node does not exist
+    pub fn degree(&self, node_index: usize) -> usize {
+        self.offsets[node_index + 1] - self.offsets[node_index]
+    }
+
+    /// Checks, if some edge has a weight other than 1
+    pub fn is_weighted(&self) -> bool {
+        self.weights.is_some()
+    }
+
+    /// Returns the number of bytes used by this struct (including its heap allocations)
+    pub fn memory_usage(&self) -> usize {
+        mem::size_of::<CompactGraph>()
+            + self.offsets.capacity() * mem::size_of::<usize>()
+            + self.neighbors.capacity() * mem::size_of::<u32>()
+            + self.weights.as_ref().map_or(0, |weights| weights.capacity() * mem::size_of::<u32>())
+    }
+
+    /// Computes the number of crossings between two free nodes `u` and `v`
+    ///
+    /// Returns `(c_uv, c_vu)`, where `c_uv` is the (weighted) number of crossings between edges of `u` and edges of `v` if `u` is ordered before `v`,
+    /// and `c_vu` is the number of crossings if `v` is ordered before `u`.
+    /// Both sorted neighbor lists are merged once, so this takes `O(deg(u) + deg(v))` time.
+    pub fn crossing_numbers(&self, u: usize, v: usize) -> (usize, usize) {
+        let neighbors_v = self.neighbors(v);
+        let weight_v = |neighbor: usize| self.weight(self.offsets[v] + neighbor);
+        let total_weight_v: usize = self.weights(v).sum();
+
+        let mut c_uv = 0;
+        let mut c_vu = 0;
+        // number and total weight of the neighbors of v that are smaller than the current neighbor of u
+        let mut number_of_smaller_neighbors = 0;
+        let mut weight_of_smaller_neighbors = 0;
+
+        for (neighbor_u, weight_u) in self.neighbors(u).iter().zip(self.weights(u)) {
+            while number_of_smaller_neighbors < neighbors_v.len()
+                && neighbors_v[number_of_smaller_neighbors] < *neighbor_u
+            {
+                weight_of_smaller_neighbors += weight_v(number_of_smaller_neighbors);
+                number_of_smaller_neighbors += 1;
+            }
+            let weight_of_equal_neighbor = if neighbors_v.get(number_of_smaller_neighbors) == Some(neighbor_u) {
+                weight_v(number_of_smaller_neighbors)
+            } else {
+                0
+            };
+
+            c_uv += weight_u * weight_of_smaller_neighbors;
+            c_vu += weight_u * (total_weight_v - weight_of_smaller_neighbors - weight_of_equal_neighbor);
+        }
+
+        (c_uv, c_vu)
+    }
+
+    /// Computes the number of crossings with all free nodes in ascending index order
+    pub fn compute_number_of_crossings_with_default_ordering(&self) -> usize {
+        let positions: Vec<usize> = (0..self.number_of_free_nodes).collect();
+        self.compute_number_of_crossings_for_positions(&positions)
+    }
+
+    /// Computes the number of crossings for a specific ordering of the free nodes
+    ///
+    /// The input ordering **must** contain all free nodes (and each exactly once), otherwise the function returns an error
+    pub fn compute_number_of_crossings_for_ordering(&self, ordering: &[usize]) -> Result<usize, Error> {
+        Error::check_ordering(ordering, self.number_of_fixed_nodes..self.number_of_nodes)?;
+
+        let mut positions = vec![0; self.number_of_free_nodes];
+        for (position, free_node_index) in ordering.iter().enumerate() {
+            positions[free_node_index - self.number_of_fixed_nodes] = position;
+        }
+
+        Ok(self.compute_number_of_crossings_for_positions(&positions))
+    }
+}
+
+// PRIVATE METHODS
+impl CompactGraph {
+    /// Counts the crossings, where `positions[i]` is the position of the free node with index `number_of_fixed_nodes + i`
+    ///
+    /// The fixed nodes are processed from left to right. Each edge crosses all edges of previous fixed nodes
+    /// that end at a free node with a greater position, whose weights are summed up with a fenwick tree in `O(log(n))`.
+    fn compute_number_of_crossings_for_positions(&self, positions: &[usize]) -> usize {
+        let mut processed_edges = FenwickTree::new(self.number_of_free_nodes);
+        let mut weight_of_processed_edges = 0;
+        let mut number_of_crossings = 0;
+
+        for fixed_node_index in 0..self.number_of_fixed_nodes {
+            let weighted_neighbor_positions = self
+                .neighbors(fixed_node_index)
+                .iter()
+                .map(|free_node_index| positions[*free_node_index as usize - self.number_of_fixed_nodes])
+                .zip(self.weights(fixed_node_index));
+
+            for (position, weight) in weighted_neighbor_positions.clone() {
+                number_of_crossings += weight * (weight_of_processed_edges - processed_edges.prefix_sum(position));
+            }
+            for (position, weight) in weighted_neighbor_positions {
+                processed_edges.add(position, weight);
+                weight_of_processed_edges += weight;
+            }
+        }
+
+        number_of_crossings
+    }
+
+    /// Returns the weight of the edge stored at the given index of `neighbors`
+    fn weight(&self, edge: usize) -> usize {
+        self.weights.as_ref().map_or(1, |weights| weights[edge] as usize)
+    }
+}
+
+/// Binary indexed tree over the positions of the free nodes
+struct FenwickTree {
+    tree: Vec<usize>,
+}
+
+impl FenwickTree {
+    fn new(size: usize) -> FenwickTree {
+        FenwickTree {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    /// Adds `value` at `position`
+    fn add(&mut self, position: usize, value: usize) {
+        let mut index = position + 1;
+        while index < self.tree.len() {
+            self.tree[index] += value;
+            index += index & index.wrapping_neg();
+        }
+    }
+
+    /// Returns the sum of all values at positions `0..=position`
+    fn prefix_sum(&self, position: usize) -> usize {
+        let mut index = position + 1;
+        let mut sum = 0;
+        while index > 0 {
+            sum += self.tree[index];
+            index -= index & index.wrapping_neg();
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Constructs a random graph with edge weights up to `maximum_weight` and a shuffled fixed layer
+    fn random_graph(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        maximum_weight: u32,
+        rng: &mut impl Rng,
+    ) -> Graph {
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+        for fixed_node_index in graph.fixed_nodes() {
+            for free_node_index in graph.free_nodes() {
+                if rng.gen_bool(0.3) {
+                    graph
+                        .add_weighted_edge(fixed_node_index, free_node_index, rng.gen_range(1..=maximum_weight))
+                        .unwrap();
+                }
+            }
+        }
+
+        let mut fixed_node_ordering: Vec<usize> = graph.fixed_nodes().collect();
+        fixed_node_ordering.shuffle(rng);
+        graph.set_fixed_node_ordering(&fixed_node_ordering).unwrap();
+        graph
+    }
+
+    /// Counts the crossings by comparing all pairs of edges in `O(m^2)`, only considering edges of the given free nodes
+    fn pairwise_crossings(graph: &Graph, ordering: &[usize], free_nodes: &[usize]) -> usize {
+        let mut positions = vec![0; graph.number_of_nodes()];
+        for fixed_node_index in graph.fixed_nodes() {
+            positions[fixed_node_index] = graph.fixed_node_position(fixed_node_index).unwrap();
+        }
+        for (position, free_node_index) in ordering.iter().enumerate() {
+            positions[*free_node_index] = position;
+        }
+        let edges: Vec<(usize, usize, u32)> = graph
+            .weighted_edges()
+            .filter(|(_, free_node_index, _)| free_nodes.contains(free_node_index))
+            .collect();
+
+        let mut number_of_crossings = 0;
+        for (fixed1, free1, weight1) in &edges {
+            for (fixed2, free2, weight2) in &edges {
+                if positions[*fixed1] < positions[*fixed2] && positions[*free2] < positions[*free1] {
+                    number_of_crossings += (*weight1 * *weight2) as usize;
+                }
+            }
+        }
+        number_of_crossings
+    }
+
+    #[test]
+    fn number_of_crossings_matches_the_pairwise_count() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for maximum_weight in [1, 4] {
+            for _ in 0..50 {
+                let graph = random_graph(rng.gen_range(1..10), rng.gen_range(1..10), maximum_weight, &mut rng);
+                let compact_graph = CompactGraph::from_graph(&graph);
+                let free_nodes: Vec<usize> = graph.free_nodes().collect();
+                let mut ordering = free_nodes.clone();
+                ordering.shuffle(&mut rng);
+
+                assert_eq!(
+                    compact_graph.compute_number_of_crossings_for_ordering(&ordering).unwrap(),
+                    pairwise_crossings(&graph, &ordering, &free_nodes)
+                );
+                assert_eq!(
+                    compact_graph.compute_number_of_crossings_with_default_ordering(),
+                    pairwise_crossings(&graph, &free_nodes, &free_nodes)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn crossing_numbers_match_the_pairwise_count() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for maximum_weight in [1, 4] {
+            for _ in 0..20 {
+                let graph = random_graph(rng.gen_range(1..8), rng.gen_range(2..8), maximum_weight, &mut rng);
+                let compact_graph = CompactGraph::from_graph(&graph);
+
+                for u in graph.free_nodes() {
+                    for v in graph.free_nodes().filter(|v| *v != u) {
+                        assert_eq!(
+                            compact_graph.crossing_numbers(u, v),
+                            (
+                                pairwise_crossings(&graph, &[u, v], &[u, v]),
+                                pairwise_crossings(&graph, &[v, u], &[u, v])
+                            )
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_orderings_are_rejected() {
+        let graph = Graph::new(2, 3);
+        let compact_graph = CompactGraph::from_graph(&graph);
+
+        assert!(compact_graph.compute_number_of_crossings_for_ordering(&[2, 3]).is_err());
+        assert!(compact_graph.compute_number_of_crossings_for_ordering(&[2, 3, 3]).is_err());
+        assert!(compact_graph.compute_number_of_crossings_for_ordering(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn fenwick_tree_computes_prefix_sums() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut fenwick_tree = FenwickTree::new(17);
+        let mut values = [0; 17];
+
+        for _ in 0..100 {
+            let position = rng.gen_range(0..17);
+            let value = rng.gen_range(0..10);
+            fenwick_tree.add(position, value);
+            values[position] += value;
+
+            for position in 0..17 {
+                assert_eq!(fenwick_tree.prefix_sum(position), values[..=position].iter().sum::<usize>());
+            }
+        }
+    }
+}
@@ -0,0 +1,648 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::Graph;
+use crate::error::Error;
+
+
+/// Constraints on the ordering of the free nodes of a `Graph`
+///
+/// `precedences` contains pairs `(x, y)` of free nodes, where `x` must be ordered before `y`.
+/// `pinned_positions` contains pairs `(x, p)`, where the free node `x` must be at position `p` of the ordering.
+/// The nodes are given by their index in the `Graph`, like in `Graph::compute_number_of_crossings_for_ordering`.
+///
+/// Before the constraints can be used, they have to be validated against a `Graph` with `OrderingConstraints::validate`.
+#[derive(Debug, Clone, Default)]
+pub struct OrderingConstraints {
+    precedences: Vec<(usize, usize)>,
+    pinned_positions: Vec<(usize, usize)>,
+}
+
+/// Ordering constraints that were checked to be satisfiable for a specific `Graph`
+///
+/// In contrast to `OrderingConstraints`, the free nodes are identified by their offset (`index - number_of_fixed_nodes`),
+/// just like the nodes of a `PenaltyDigraph`.
+/// `release_positions` and `deadline_positions` are the first and the last position each node can be placed at,
+/// derived from the pinned positions and the precedences.
+#[derive(Debug, Clone)]
+pub struct ValidatedConstraints {
+    number_of_nodes: usize,
+    predecessors: Vec<Vec<usize>>,
+    successors: Vec<Vec<usize>>,
+    pinned_positions: Vec<Option<usize>>,
+    pinned_nodes: Vec<Option<usize>>,
+    topological_order: Vec<usize>,
+    release_positions: Vec<usize>,
+    deadline_positions: Vec<usize>,
+}
+
+// PUBLIC METHODS
+impl OrderingConstraints {
+    /// Constructs new `OrderingConstraints` without any constraints
+    pub fn new() -> OrderingConstraints {
+        OrderingConstraints::default()
+    }
+
+    /// Requires the free node `before` to be ordered before the free node `after`
+    pub fn add_precedence(&mut self, before: usize, after: usize) {
+        self.precedences.push((before, after));
+    }
+
+    /// Requires the free node `node` to be at `position` of the ordering
+    pub fn pin(&mut self, node: usize, position: usize) {
+        self.pinned_positions.push((node, position));
+    }
+
+    pub fn precedences(&self) -> &[(usize, usize)] {
+        &self.precedences
+    }
+
+    pub fn pinned_positions(&self) -> &[(usize, usize)] {
+        &self.pinned_positions
+    }
+
+    /// Checks, if an ordering of the free nodes (given by their index) satisfies all constraints
+    pub fn is_satisfied_by(&self, ordering: &[usize]) -> bool {
+        let positions: HashMap<usize, usize> = ordering
+            .iter()
+            .enumerate()
+            .map(|(position, node)| (*node, position))
+            .collect();
+        let position = |node: usize| positions.get(&node).copied();
+
+        self.pinned_positions
+            .iter()
+            .all(|(node, pinned_position)| position(*node) == Some(*pinned_position))
+            && self.precedences.iter().all(|(before, after)| {
+                matches!((position(*before), position(*after)), (Some(p), Some(q)) if p < q)
+            })
+    }
+
+    /// Checks that the constraints refer to free nodes of the graph and can be satisfied at the same time
+    ///
+    /// Returns `Error::IndexError` for labels that are not free nodes, and `Error::InfeasibleConstraints`
+    /// for duplicate pins, cyclic precedences and pins that contradict each other or the precedences.
+    pub fn validate(&self, graph: &Graph) -> Result<ValidatedConstraints, Error> {
+        let number_of_fixed_nodes = graph.number_of_fixed_nodes();
+        let number_of_nodes = graph.number_of_free_nodes();
+        let offset = |node: usize| {
+            if graph.free_nodes().contains(&node) {
+                Ok(node - number_of_fixed_nodes)
+            } else {
+                Err(Error::IndexError {
+                    index: node,
+                    range: graph.free_nodes(),
+                })
+            }
+        };
+
+        let mut predecessors = vec![Vec::new(); number_of_nodes];
+        let mut successors = vec![Vec::new(); number_of_nodes];
+        let mut included_precedences = HashSet::new();
+        for (before, after) in &self.precedences {
+            let (before, after) = (offset(*before)?, offset(*after)?);
+            if before == after {
+                return Err(Error::InfeasibleConstraints(format!(
+                    "The node {} can not be ordered before itself",
+                    before + number_of_fixed_nodes
+                )));
+            }
+            if included_precedences.insert((before, after)) {
+                predecessors[after].push(before);
+                successors[before].push(after);
+            }
+        }
+
+        let mut pinned_positions = vec![None; number_of_nodes];
+        let mut pinned_nodes = vec![None; number_of_nodes];
+        for (node, position) in &self.pinned_positions {
+            let node = offset(*node)?;
+            if *position >= number_of_nodes {
+                return Err(Error::InfeasibleConstraints(format!(
+                    "The position {position} does not exist, there are only {number_of_nodes} free nodes"
+                )));
+            }
+            if pinned_positions[node].is_some_and(|pinned_position| pinned_position != *position) {
+                return Err(Error::InfeasibleConstraints(format!(
+                    "The node {} is pinned to more than one position",
+                    node + number_of_fixed_nodes
+                )));
+            }
+            if pinned_nodes[*position].is_some_and(|pinned_node| pinned_node != node) {
+                return Err(Error::InfeasibleConstraints(format!(
+                    "More than one node is pinned to position {position}"
+                )));
+            }
+            pinned_positions[node] = Some(*position);
+            pinned_nodes[*position] = Some(node);
+        }
+
+        let topological_order = ValidatedConstraints::compute_topological_order(&predecessors, &successors)
+            .map_err(|cycle| {
+                let cycle: Vec<usize> = cycle.iter().map(|node| node + number_of_fixed_nodes).collect();
+                Error::InfeasibleConstraints(format!("The precedences contain the cycle {cycle:?}"))
+            })?;
+
+        let mut constraints = ValidatedConstraints {
+            number_of_nodes,
+            predecessors,
+            successors,
+            pinned_positions,
+            pinned_nodes,
+            topological_order,
+            release_positions: Vec::new(),
+            deadline_positions: Vec::new(),
+        };
+        constraints.compute_windows().map_err(|node| {
+            Error::InfeasibleConstraints(format!(
+                "The node {} can not be placed without violating the pinned positions",
+                node + number_of_fixed_nodes
+            ))
+        })?;
+
+        let priorities: Vec<usize> = (0..number_of_nodes).collect();
+        if constraints.schedule(&priorities, false).is_none() {
+            return Err(Error::InfeasibleConstraints(
+                "The pinned positions and precedences can not be satisfied at the same time".to_string(),
+            ));
+        }
+
+        Ok(constraints)
+    }
+}
+
+// PUBLIC METHODS
+impl ValidatedConstraints {
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    /// Returns the nodes that must be ordered directly before `node` (not the transitive ones)
+    pub fn predecessors(&self, node: usize) -> &[usize] {
+        &self.predecessors[node]
+    }
+
+    /// Returns the nodes that must be ordered directly after `node` (not the transitive ones)
+    pub fn successors(&self, node: usize) -> &[usize] {
+        &self.successors[node]
+    }
+
+    /// Returns the position `node` is pinned to
+    pub fn pinned_position(&self, node: usize) -> Option<usize> {
+        self.pinned_positions[node]
+    }
+
+    /// Checks, if there are pinned positions
+    pub fn has_pinned_positions(&self) -> bool {
+        self.pinned_nodes.iter().any(Option::is_some)
+    }
+
+    /// Returns all nodes in an order that respects the precedences (smaller nodes first, if possible)
+    pub fn topological_order(&self) -> &[usize] {
+        &self.topological_order
+    }
+
+    /// Checks, if an ordering of the nodes satisfies all constraints
+    pub fn is_satisfied_by(&self, ordering: &[usize]) -> bool {
+        if ordering.len() != self.number_of_nodes {
+            return false;
+        }
+
+        let mut positions = vec![usize::MAX; self.number_of_nodes];
+        for (position, node) in ordering.iter().enumerate() {
+            positions[*node] = position;
+        }
+
+        (0..self.number_of_nodes).all(|node| {
+            self.pinned_positions[node].is_none_or(|pinned_position| positions[node] == pinned_position)
+                && self.successors[node]
+                    .iter()
+                    .all(|successor| positions[node] < positions[*successor])
+        })
+    }
+
+    /// Checks, if swapping the nodes at `position` and `position + 1` keeps a valid ordering valid
+    pub fn allows_swap(&self, ordering: &[usize], position: usize) -> bool {
+        let (u, v) = (ordering[position], ordering[position + 1]);
+
+        self.pinned_positions[u].is_none()
+            && self.pinned_positions[v].is_none()
+            && !self.successors[u].contains(&v)
+    }
+
+    /// Checks, if moving the node at position `from` to position `to` keeps a valid ordering valid
+    ///
+    /// All nodes between both positions are shifted by one, so none of them may be pinned.
+    /// Since the ordering was valid before, it is enough to check the direct precedences of the moved node.
+    pub fn allows_move(&self, ordering: &[usize], from: usize, to: usize) -> bool {
+        let node = ordering[from];
+        let passed_nodes = if from < to {
+            &ordering[from + 1..=to]
+        } else {
+            &ordering[to..from]
+        };
+        let blocking_nodes = if from < to {
+            &self.successors[node]
+        } else {
+            &self.predecessors[node]
+        };
+
+        from == to
+            || (self.pinned_positions[node].is_none()
+                && passed_nodes.iter().all(|passed_node| {
+                    self.pinned_positions[*passed_node].is_none() && !blocking_nodes.contains(passed_node)
+                }))
+    }
+
+    /// Constructs an ordering that satisfies all constraints and stays as close as possible to the given ordering
+    ///
+    /// If the given ordering already satisfies the constraints, it is returned unchanged.
+    /// Otherwise the nodes are placed position by position, taking the first available node of the given ordering,
+    /// unless a node has to be placed earlier to reach a pinned position in time.
+    pub fn enforce(&self, ordering: &[usize]) -> Vec<usize> {
+        if self.is_satisfied_by(ordering) {
+            return ordering.to_vec();
+        }
+
+        let mut priorities = vec![0; self.number_of_nodes];
+        for (priority, node) in ordering.iter().enumerate() {
+            priorities[*node] = priority;
+        }
+
+        self.schedule(&priorities, true)
+            .or_else(|| self.schedule(&priorities, false))
+            .expect("The constraints were validated, so the earliest deadline schedule exists")
+    }
+}
+
+// PRIVATE METHODS
+impl ValidatedConstraints {
+    /// Computes a topological order with Kahn's algorithm, or returns a cycle
+    fn compute_topological_order(
+        predecessors: &[Vec<usize>],
+        successors: &[Vec<usize>],
+    ) -> Result<Vec<usize>, Vec<usize>> {
+        let mut remaining_predecessors: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+        let mut ready: BTreeSet<usize> = (0..predecessors.len())
+            .filter(|node| remaining_predecessors[*node] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(predecessors.len());
+
+        while let Some(node) = ready.pop_first() {
+            order.push(node);
+            for successor in &successors[node] {
+                remaining_predecessors[*successor] -= 1;
+                if remaining_predecessors[*successor] == 0 {
+                    ready.insert(*successor);
+                }
+            }
+        }
+
+        if order.len() == predecessors.len() {
+            return Ok(order);
+        }
+
+        // Every remaining node has a remaining predecessor, so walking backwards eventually repeats a node.
+        let mut node = (0..predecessors.len())
+            .find(|node| remaining_predecessors[*node] > 0)
+            .expect("Some node was not ordered");
+        let mut visited = vec![None; predecessors.len()];
+        let mut path = Vec::new();
+        while visited[node].is_none() {
+            visited[node] = Some(path.len());
+            path.push(node);
+            node = *predecessors[node]
+                .iter()
+                .find(|predecessor| remaining_predecessors[**predecessor] > 0)
+                .expect("A remaining node has a remaining predecessor");
+        }
+        let mut cycle = path.split_off(visited[node].expect("The node was visited"));
+        cycle.reverse();
+
+        Err(cycle)
+    }
+
+    /// Computes the first and last position of every node, or returns a node whose window is empty
+    ///
+    /// Unpinned nodes can not be placed at pinned positions, so their windows skip them.
+    fn compute_windows(&mut self) -> Result<(), usize> {
+        let number_of_nodes = self.number_of_nodes;
+        let is_pinned_position = |position: usize| self.pinned_nodes[position].is_some();
+
+        let mut release_positions = vec![0; number_of_nodes];
+        for node in self.topological_order.iter().copied() {
+            let mut release = self.predecessors[node]
+                .iter()
+                .map(|predecessor| release_positions[*predecessor] + 1)
+                .max()
+                .unwrap_or(0);
+            if let Some(pinned_position) = self.pinned_positions[node] {
+                if release > pinned_position {
+                    return Err(node);
+                }
+                release = pinned_position;
+            } else {
+                while release < number_of_nodes && is_pinned_position(release) {
+                    release += 1;
+                }
+            }
+            release_positions[node] = release;
+        }
+
+        let mut deadline_positions = vec![number_of_nodes; number_of_nodes];
+        for node in self.topological_order.iter().rev().copied() {
+            let mut deadline = self.successors[node]
+                .iter()
+                .map(|successor| deadline_positions[*successor].checked_sub(1))
+                .min()
+                .unwrap_or(number_of_nodes.checked_sub(1))
+                .ok_or(node)?;
+            if let Some(pinned_position) = self.pinned_positions[node] {
+                if deadline < pinned_position {
+                    return Err(node);
+                }
+                deadline = pinned_position;
+            } else {
+                while is_pinned_position(deadline) {
+                    deadline = deadline.checked_sub(1).ok_or(node)?;
+                }
+            }
+            if deadline < release_positions[node] {
+                return Err(node);
+            }
+            deadline_positions[node] = deadline;
+        }
+
+        self.release_positions = release_positions;
+        self.deadline_positions = deadline_positions;
+        Ok(())
+    }
+
+    /// Places the nodes position by position, returns `None` if some node misses its deadline
+    ///
+    /// At each position, the pinned node (if any) is placed. Otherwise the placed node is chosen among the released nodes
+    /// whose predecessors have all been placed: by earliest deadline (ties broken by priority), or, if `prefer_priorities` is set,
+    /// by priority as long as the remaining deadlines can still be met.
+    /// Earliest deadline first always finds a valid ordering, if there is one.
+    fn schedule(&self, priorities: &[usize], prefer_priorities: bool) -> Option<Vec<usize>> {
+        let number_of_nodes = self.number_of_nodes;
+        let mut remaining_predecessors: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut ready_by_deadline = BTreeSet::new();
+        let mut ready_by_priority = BTreeSet::new();
+        let mut unreleased = BTreeSet::new();
+        let mut ordering = Vec::with_capacity(number_of_nodes);
+
+        // number of positions in `0..p` that are not pinned
+        let mut free_positions_before = vec![0; number_of_nodes + 1];
+        for position in 0..number_of_nodes {
+            free_positions_before[position + 1] =
+                free_positions_before[position] + usize::from(self.pinned_nodes[position].is_none());
+        }
+        // deadlines of the unplaced unpinned nodes that can not simply go last
+        let mut pending_deadlines: BTreeSet<(usize, usize)> = (0..number_of_nodes)
+            .filter(|node| {
+                self.pinned_positions[*node].is_none() && self.deadline_positions[*node] + 1 < number_of_nodes
+            })
+            .map(|node| (self.deadline_positions[node], node))
+            .collect();
+
+        let make_available = |node: usize, unreleased: &mut BTreeSet<(usize, usize)>| {
+            if self.pinned_positions[node].is_none() {
+                unreleased.insert((self.release_positions[node], node));
+            }
+        };
+        for node in (0..number_of_nodes).filter(|node| self.predecessors[*node].is_empty()) {
+            make_available(node, &mut unreleased);
+        }
+
+        for position in 0..number_of_nodes {
+            while let Some((release, node)) = unreleased.first().copied() {
+                if release > position {
+                    break;
+                }
+                unreleased.pop_first();
+                ready_by_deadline.insert((self.deadline_positions[node], priorities[node], node));
+                ready_by_priority.insert((priorities[node], node));
+            }
+
+            let node = if let Some(pinned_node) = self.pinned_nodes[position] {
+                if remaining_predecessors[pinned_node] > 0 {
+                    return None;
+                }
+                pinned_node
+            } else {
+                let earliest_deadline_node = ready_by_deadline.first()?.2;
+                let mut node = earliest_deadline_node;
+                if prefer_priorities {
+                    let first_node = ready_by_priority.first()?.1;
+                    if self.can_meet_deadlines(position, first_node, &pending_deadlines, &free_positions_before) {
+                        node = first_node;
+                    }
+                }
+                if self.deadline_positions[node] < position {
+                    return None;
+                }
+                ready_by_deadline.remove(&(self.deadline_positions[node], priorities[node], node));
+                ready_by_priority.remove(&(priorities[node], node));
+                pending_deadlines.remove(&(self.deadline_positions[node], node));
+                node
+            };
+
+            ordering.push(node);
+            for successor in &self.successors[node] {
+                remaining_predecessors[*successor] -= 1;
+                if remaining_predecessors[*successor] == 0 {
+                    make_available(*successor, &mut unreleased);
+                }
+            }
+        }
+
+        Some(ordering)
+    }
+
+    /// Checks, if after placing `node` at `position` every pending deadline `d` still has enough free positions in `position + 1..=d`
+    fn can_meet_deadlines(
+        &self,
+        position: usize,
+        node: usize,
+        pending_deadlines: &BTreeSet<(usize, usize)>,
+        free_positions_before: &[usize],
+    ) -> bool {
+        let mut number_of_due_nodes = 0;
+        for (deadline, pending_node) in pending_deadlines {
+            if *pending_node == node {
+                continue;
+            }
+            if *deadline <= position {
+                return false;
+            }
+            number_of_due_nodes += 1;
+            if number_of_due_nodes > free_positions_before[deadline + 1] - free_positions_before[position + 1] {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMBER_OF_FIXED_NODES: usize = 2;
+
+    fn graph(number_of_free_nodes: usize) -> Graph {
+        Graph::new(NUMBER_OF_FIXED_NODES, number_of_free_nodes)
+    }
+
+    /// Returns the index of the `i`-th free node
+    fn free(i: usize) -> usize {
+        NUMBER_OF_FIXED_NODES + i
+    }
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+
+        let mut extended_permutations = Vec::new();
+        for permutation in permutations(n - 1) {
+            for position in 0..=permutation.len() {
+                let mut permutation = permutation.clone();
+                permutation.insert(position, n - 1);
+                extended_permutations.push(permutation);
+            }
+        }
+        extended_permutations
+    }
+
+    #[test]
+    fn cyclic_precedences_are_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(0), free(1));
+        constraints.add_precedence(free(1), free(2));
+        constraints.add_precedence(free(2), free(0));
+
+        assert!(matches!(
+            constraints.validate(&graph(4)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn precedence_of_a_node_before_itself_is_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(1), free(1));
+
+        assert!(matches!(
+            constraints.validate(&graph(2)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn two_pins_on_the_same_position_are_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.pin(free(0), 1);
+        constraints.pin(free(2), 1);
+
+        assert!(matches!(
+            constraints.validate(&graph(3)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn repeated_pin_is_feasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.pin(free(0), 1);
+        constraints.pin(free(0), 1);
+
+        let validated = constraints.validate(&graph(3)).unwrap();
+        assert_eq!(validated.pinned_position(0), Some(1));
+    }
+
+    #[test]
+    fn pin_that_conflicts_with_a_precedence_is_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(0), free(1));
+        constraints.pin(free(1), 0);
+
+        assert!(matches!(
+            constraints.validate(&graph(3)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn pin_that_leaves_too_few_positions_for_the_successors_is_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(0), free(1));
+        constraints.add_precedence(free(1), free(2));
+        constraints.pin(free(0), 1);
+
+        assert!(matches!(
+            constraints.validate(&graph(3)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn pins_on_both_sides_of_a_precedence_in_the_wrong_order_are_infeasible() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(0), free(1));
+        constraints.pin(free(0), 3);
+        constraints.pin(free(1), 2);
+
+        assert!(matches!(
+            constraints.validate(&graph(4)),
+            Err(Error::InfeasibleConstraints(_))
+        ));
+    }
+
+    #[test]
+    fn constraints_on_nodes_that_are_not_free_are_rejected() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(0, free(1));
+
+        assert!(matches!(
+            constraints.validate(&graph(2)),
+            Err(Error::IndexError { .. })
+        ));
+    }
+
+    #[test]
+    fn enforce_satisfies_the_constraints() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(4), free(0));
+        constraints.add_precedence(free(0), free(2));
+        constraints.add_precedence(free(3), free(2));
+        constraints.pin(free(1), 1);
+        constraints.pin(free(5), 4);
+        let validated = constraints.validate(&graph(6)).unwrap();
+
+        for ordering in permutations(6) {
+            let enforced = validated.enforce(&ordering);
+
+            assert!(validated.is_satisfied_by(&enforced), "{ordering:?} -> {enforced:?}");
+            let enforced_indices: Vec<usize> = enforced.iter().map(|node| free(*node)).collect();
+            assert!(constraints.is_satisfied_by(&enforced_indices));
+            if validated.is_satisfied_by(&ordering) {
+                assert_eq!(enforced, ordering);
+            }
+        }
+    }
+
+    #[test]
+    fn is_satisfied_by_agrees_for_both_representations() {
+        let mut constraints = OrderingConstraints::new();
+        constraints.add_precedence(free(2), free(0));
+        constraints.pin(free(3), 0);
+        let validated = constraints.validate(&graph(4)).unwrap();
+
+        for ordering in permutations(4) {
+            let indices: Vec<usize> = ordering.iter().map(|node| free(*node)).collect();
+            assert_eq!(validated.is_satisfied_by(&ordering), constraints.is_satisfied_by(&indices));
+        }
+    }
+}
@@ -1,6 +1,20 @@
-use std::{collections::HashSet, fmt::Debug, iter};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{self, Debug},
+    iter,
+    str::FromStr,
+};
 
-use super::Graph;
+use super::{
+    compact_graph::CompactGraph,
+    constraints::{OrderingConstraints, ValidatedConstraints},
+    Graph,
+};
+use crate::error::Error;
+
+/// Maximum number of nodes for which `PenaltyDigraph::exact_fas` is supported (the dynamic program needs `O(2^n)` memory)
+pub const MAX_EXACT_FAS_NODES: usize = 20;
 
 
 /// Default representation of a penalty digraph for the OCM problem
@@ -10,59 +24,87 @@ use super::Graph;
 /// 
 /// In the conversion, each edge `a -> b` represents that the number of crossings caused by a node `a` and a node `b` are minimal, when node `a` is ordered before node `b`.
 /// Solving the DFAS problem for this (maybe acyclic) digraph corresponds to solving the OCM problem on the regular `Graph`.
+///
+/// Each edge `a -> b` is weighted with `c_ba - c_ab`, i.e. the number of additional crossings if `b` is ordered before `a` anyway.
+/// For graphs with weighted edges, the crossing numbers are weighted as well.
+///
+/// `constraints` optionally restricts the orderings. Each precedence `a` before `b` becomes a forced edge `a -> b`
+/// (replacing the penalty edge `b -> a`, if any), which the computed orderings never violate.
 #[derive(Debug)]
 pub struct PenaltyDigraph {
     number_of_nodes: usize,
-    adjacency_list: Vec<HashSet<usize>>,
+    adjacency_list: Vec<HashMap<usize, usize>>,
+    constraints: Option<ValidatedConstraints>,
+}
+
+/// Algorithm used to compute an ordering with a small feedback arc set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FasMethod {
+    /// Insertion heuristic, see `PenaltyDigraph::sort_fas`
+    SortFas,
+    /// Sink/source greedy, see `PenaltyDigraph::eades_lin_smyth`
+    EadesLinSmyth,
+    /// Pivot algorithm, see `PenaltyDigraph::kwik_sort`
+    KwikSort,
+}
+
+impl FasMethod {
+    pub const ALL: [FasMethod; 3] = [FasMethod::SortFas, FasMethod::EadesLinSmyth, FasMethod::KwikSort];
+}
+
+impl fmt::Display for FasMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FasMethod::SortFas => "sort_fas",
+            FasMethod::EadesLinSmyth => "eades_lin_smyth",
+            FasMethod::KwikSort => "kwik_sort",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for FasMethod {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "sort_fas" | "sortfas" => Ok(FasMethod::SortFas),
+            "eades_lin_smyth" | "els" => Ok(FasMethod::EadesLinSmyth),
+            "kwik_sort" | "kwiksort" => Ok(FasMethod::KwikSort),
+            _ => Err(Error::ValueError(format!("Unknown FAS method '{name}'"))),
+        }
+    }
 }
 
 // CONSTRUCTORS
 impl PenaltyDigraph {
     /// Constructs a new `PenaltyDigraph` without edges
     pub fn new(number_of_nodes: usize) -> PenaltyDigraph {
-        let adjacency_list = iter::repeat(HashSet::new()).take(number_of_nodes).collect();
+        let adjacency_list = iter::repeat_n(HashMap::new(), number_of_nodes).collect();
 
         PenaltyDigraph {
             number_of_nodes,
             adjacency_list,
+            constraints: None,
         }
     }
 
     /// Constructs a new `PenaltyDigraph` by applying the algorithm described in [this paper](https://dl.acm.org/doi/abs/10.1145/945394.945396).
     pub fn from_graph(graph: &Graph) -> PenaltyDigraph {
-        let mut penalty_digraph = PenaltyDigraph::new(graph.number_of_free_nodes);
-
-        for u in graph.number_of_fixed_nodes..graph.number_of_nodes {
-            for v in u + 1..graph.number_of_nodes {
-                let mut c_uv: isize = 0;
-                let mut c_vu: isize = 0;
-                let mut scan;
-                let degree_v = graph.adjacency_list.get(v).expect("Must exist").len() as isize;
-
-                let mut adj_u_iter = graph.adjacency_list.get(u).unwrap().iter();
-                let mut adj_u = adj_u_iter.next();
-
-                let mut adj_v_iter = graph.adjacency_list.get(v).unwrap().iter();
-                let mut adj_v = adj_v_iter.next();
-
-                while adj_u.is_some() {
-                    scan = 0;
-                    while adj_v.is_some() && adj_v.unwrap() < adj_u.unwrap() {
-                        adj_v = adj_v_iter.next();
-                        scan += 1;
-                    }
-                    c_uv += scan;
-                    c_vu = c_vu + degree_v - scan - 1;
+        PenaltyDigraph::from_compact_graph(&CompactGraph::from_graph(graph))
+    }
 
-                    if adj_u < adj_v {
-                        c_vu += 1;
-                    }
-                    adj_u = adj_u_iter.next();
-                }
+    /// Constructs a new `PenaltyDigraph` from the frozen representation of a `Graph`
+    pub fn from_compact_graph(graph: &CompactGraph) -> PenaltyDigraph {
+        let mut penalty_digraph = PenaltyDigraph::new(graph.number_of_free_nodes());
+
+        for u in graph.number_of_fixed_nodes()..graph.number_of_nodes() {
+            for v in u + 1..graph.number_of_nodes() {
+                let (c_uv, c_vu) = graph.crossing_numbers(u, v);
 
                 penalty_digraph.add_crossings(
-                    u - graph.number_of_fixed_nodes,
-                    v - graph.number_of_fixed_nodes,
+                    u - graph.number_of_fixed_nodes(),
+                    v - graph.number_of_fixed_nodes(),
                     c_uv,
                     c_vu,
                 );
@@ -71,6 +113,29 @@ impl PenaltyDigraph {
 
         penalty_digraph
     }
+
+    /// Constructs a new `PenaltyDigraph` whose orderings respect the given constraints
+    ///
+    /// Returns an error, if the constraints do not fit the graph or can not be satisfied.
+    pub fn from_graph_with_constraints(
+        graph: &Graph,
+        constraints: &OrderingConstraints,
+    ) -> Result<PenaltyDigraph, Error> {
+        let constraints = constraints.validate(graph)?;
+        let mut penalty_digraph = PenaltyDigraph::from_graph(graph);
+
+        for before in 0..constraints.number_of_nodes() {
+            for after in constraints.successors(before) {
+                penalty_digraph.adjacency_list[*after].remove(&before);
+                if !penalty_digraph.edge_exists(before, *after) {
+                    penalty_digraph.add_edge(before, *after, 0);
+                }
+            }
+        }
+        penalty_digraph.constraints = Some(constraints);
+
+        Ok(penalty_digraph)
+    }
 }
 
 // PUBLIC METHODS
@@ -79,41 +144,100 @@ impl PenaltyDigraph {
     /// 
     /// Always adds the edge in the direction with less crossings. 
     /// If the crossings in both orderings are equal, no edge is added.
-    fn add_crossings(&mut self, u: usize, v: usize, c_uv: isize, c_vu: isize) {
-        if c_vu < c_uv {
-            self.add_edge(u, v);
-        } else if c_uv < c_vu {
-            self.add_edge(v, u);
+    fn add_crossings(&mut self, u: usize, v: usize, c_uv: usize, c_vu: usize) {
+        if c_uv < c_vu {
+            self.add_edge(u, v, c_vu - c_uv);
+        } else if c_vu < c_uv {
+            self.add_edge(v, u, c_uv - c_vu);
         }
     }
 
     // Adds an edge between two nodes
-    fn add_edge(&mut self, u: usize, v: usize) -> bool {
-        self.adjacency_list.get_mut(u).unwrap().insert(v)
+    fn add_edge(&mut self, u: usize, v: usize, weight: usize) -> bool {
+        self.adjacency_list.get_mut(u).unwrap().insert(v, weight).is_none()
     }
 
     /// Checks, if an edge between two nodes exists
     fn edge_exists(&self, u: usize, v: usize) -> bool {
-        self.adjacency_list.get(u).unwrap().contains(&v)
+        self.adjacency_list.get(u).unwrap().contains_key(&v)
+    }
+
+    /// Returns the validated constraints of this digraph, if any
+    pub fn constraints(&self) -> Option<&ValidatedConstraints> {
+        self.constraints.as_ref()
+    }
+
+    /// Returns the weight of the edge from `u` to `v`, or `None` if the edge does not exist
+    pub fn edge_weight(&self, u: usize, v: usize) -> Option<usize> {
+        self.adjacency_list.get(u)?.get(&v).copied()
+    }
+
+    pub fn number_of_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    /// Returns all edges as `(u, v, weight)` triples, sorted by `u` first and by `v` second
+    pub fn edges(&self) -> Vec<(usize, usize, usize)> {
+        (0..self.number_of_nodes)
+            .flat_map(|u| {
+                self.sorted_successors(u)
+                    .into_iter()
+                    .map(move |v| (u, v, self.adjacency_list[u][&v]))
+            })
+            .collect()
+    }
+
+    /// Returns the edges `u -> v` with `v` ordered before `u`, i.e. the feedback arc set of the ordering
+    ///
+    /// Without constraints, the sum of their weights is the number of crossings the ordering has more than the lower bound.
+    pub fn feedback_arcs(&self, ordering: &[usize]) -> Result<Vec<(usize, usize, usize)>, Error> {
+        Error::check_ordering(ordering, 0..self.number_of_nodes)?;
+
+        let mut positions = vec![0; self.number_of_nodes];
+        for (position, node) in ordering.iter().enumerate() {
+            positions[*node] = position;
+        }
+
+        Ok(self
+            .edges()
+            .into_iter()
+            .filter(|(u, v, _)| positions[*v] < positions[*u])
+            .collect())
+    }
+
+    /// Checks, if the edge from `u` to `v` is forced by a precedence of the constraints
+    pub fn is_forced_edge(&self, u: usize, v: usize) -> bool {
+        self.is_forced_before(u, v)
     }
 
     /// Computes an ordering that would solve the DFAS problem
     /// 
     /// This algorithm is described in [this paper](https://arxiv.org/pdf/2208.09234.pdf)
+    ///
+    /// With constraints, the nodes are inserted in a topological order of the precedences and never in front of a forced predecessor.
+    /// Pinned positions are enforced afterwards.
     pub fn sort_fas(&self) -> Vec<usize> {
+        let insertion_order: Vec<usize> = match &self.constraints {
+            Some(constraints) => constraints.topological_order().to_vec(),
+            None => (0..self.number_of_nodes).collect(),
+        };
+
         let mut feedback_arc_set: Vec<usize> = Vec::new();
-        for u in 0..self.number_of_nodes {
+        for u in insertion_order {
             let mut val: isize = 0;
             let mut min: isize = 0;
-            let mut loc: usize = u;
+            let mut loc: usize = feedback_arc_set.len();
 
             for j in (0..loc).rev() {
                 let v = feedback_arc_set.get(j).expect("Index exists");
+                if self.is_forced_before(*v, u) {
+                    break;
+                }
                 if self.edge_exists(u, *v) {
-                    val += 1;
+                    val -= 1;
                 }
                 if self.edge_exists(*v, u) {
-                    val -= 1;
+                    val += 1;
                 }
 
                 if val <= min {
@@ -124,6 +248,660 @@ impl PenaltyDigraph {
             feedback_arc_set.insert(loc, u);
         }
 
-        feedback_arc_set
+        self.enforce_constraints(feedback_arc_set)
+    }
+
+    /// Computes an ordering with the given FAS method
+    pub fn compute_fas(&self, method: FasMethod) -> Vec<usize> {
+        match method {
+            FasMethod::SortFas => self.sort_fas(),
+            FasMethod::EadesLinSmyth => self.eades_lin_smyth(),
+            FasMethod::KwikSort => self.kwik_sort(),
+        }
+    }
+
+    /// Computes an ordering by applying a FAS method to each strongly connected component separately
+    ///
+    /// An edge between two different components is never part of an optimal feedback arc set,
+    /// so the components are ordered topologically and only the nodes within a component are ordered by `method`.
+    /// Components with a single node need no further work, so acyclic parts of the digraph are ordered in linear time.
+    /// Components with at most `exact_threshold` nodes (capped at `MAX_EXACT_FAS_NODES`) are ordered optimally with `PenaltyDigraph::exact_fas`.
+    ///
+    /// The precedences of the constraints are respected by the exact solutions, the other components are solved without the constraints
+    /// (all forced edges are still contained in the digraph). The combined ordering is repaired afterwards as described in `ValidatedConstraints::enforce`.
+    pub fn compute_fas_by_components(&self, method: FasMethod, exact_threshold: usize) -> Vec<usize> {
+        let exact_threshold = exact_threshold.min(MAX_EXACT_FAS_NODES);
+        let mut ordering = Vec::with_capacity(self.number_of_nodes);
+
+        for component in self.strongly_connected_components() {
+            if component.len() == 1 {
+                ordering.extend(component);
+            } else if component.len() <= exact_threshold {
+                let subdigraph = self.induced_subdigraph(&component);
+                let forced_predecessors = self.forced_predecessor_masks(&component);
+                let component_ordering = subdigraph.compute_exact_ordering(&forced_predecessors);
+                ordering.extend(component_ordering.into_iter().map(|node| component[node]));
+            } else {
+                let component_ordering = self.induced_subdigraph(&component).compute_fas(method);
+                ordering.extend(component_ordering.into_iter().map(|node| component[node]));
+            }
+        }
+
+        self.enforce_constraints(ordering)
+    }
+
+    /// Computes an ordering with the minimum total weight of backward edges, i.e. an optimal solution of the DFAS problem
+    ///
+    /// Uses a dynamic program over all subsets of nodes (the nodes in front of the next node), which takes `O(2^n * n^2)` time.
+    /// The precedences of the constraints are respected, so with precedences only the result is optimal among all valid orderings.
+    /// Pinned positions are enforced afterwards, which can make the result suboptimal.
+    ///
+    /// Returns an error if the digraph has more than `MAX_EXACT_FAS_NODES` nodes.
+    pub fn exact_fas(&self) -> Result<Vec<usize>, Error> {
+        if self.number_of_nodes > MAX_EXACT_FAS_NODES {
+            return Err(Error::ValueError(format!(
+                "The exact FAS computation supports at most {MAX_EXACT_FAS_NODES} nodes"
+            )));
+        }
+
+        let nodes: Vec<usize> = (0..self.number_of_nodes).collect();
+        let ordering = self.compute_exact_ordering(&self.forced_predecessor_masks(&nodes));
+        Ok(self.enforce_constraints(ordering))
+    }
+
+    /// Computes the strongly connected components with Tarjan's algorithm in `O(n + m)`
+    ///
+    /// The components are returned in topological order, i.e. every edge between two components points to a later component.
+    /// Nodes and edges are visited in ascending index order, so the result is deterministic.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut indices: Vec<Option<usize>> = vec![None; self.number_of_nodes];
+        let mut low_links = vec![0; self.number_of_nodes];
+        let mut is_on_stack = vec![false; self.number_of_nodes];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..self.number_of_nodes {
+            if indices[root].is_some() {
+                continue;
+            }
+
+            // each frame holds a node, its sorted successors and the number of successors that were processed
+            let mut call_stack: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+            indices[root] = Some(next_index);
+            low_links[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            is_on_stack[root] = true;
+            call_stack.push((root, self.sorted_successors(root), 0));
+
+            while let Some((node, successors, processed)) = call_stack.last_mut() {
+                let node = *node;
+                if let Some(successor) = successors.get(*processed).copied() {
+                    *processed += 1;
+                    match indices[successor] {
+                        None => {
+                            indices[successor] = Some(next_index);
+                            low_links[successor] = next_index;
+                            next_index += 1;
+                            stack.push(successor);
+                            is_on_stack[successor] = true;
+                            call_stack.push((successor, self.sorted_successors(successor), 0));
+                        }
+                        Some(index) if is_on_stack[successor] => {
+                            low_links[node] = low_links[node].min(index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some((parent, _, _)) = call_stack.last() {
+                    low_links[*parent] = low_links[*parent].min(low_links[node]);
+                }
+                if Some(low_links[node]) == indices[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().expect("The node is still on the stack");
+                        is_on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        // Tarjan's algorithm finds the components in reverse topological order
+        components.reverse();
+        components
+    }
+
+    /// Constructs the subdigraph induced by the given nodes, node `nodes[i]` becomes node `i`
+    ///
+    /// The constraints are not carried over.
+    pub fn induced_subdigraph(&self, nodes: &[usize]) -> PenaltyDigraph {
+        let new_indices: HashMap<usize, usize> =
+            nodes.iter().enumerate().map(|(new_index, node)| (*node, new_index)).collect();
+        let mut subdigraph = PenaltyDigraph::new(nodes.len());
+
+        for (new_index, node) in nodes.iter().enumerate() {
+            for (successor, weight) in &self.adjacency_list[*node] {
+                if let Some(new_successor) = new_indices.get(successor) {
+                    subdigraph.add_edge(new_index, *new_successor, *weight);
+                }
+            }
+        }
+
+        subdigraph
+    }
+
+    /// Computes an ordering with the greedy algorithm of Eades, Lin and Smyth
+    ///
+    /// Sinks are repeatedly removed and placed at the end of the ordering, sources are removed and placed at the front.
+    /// If there is neither a sink nor a source, the node with the largest difference between the weights of its outgoing
+    /// and incoming edges is placed at the front. Runs in `O((n + m) log(n))`.
+    ///
+    /// With constraints, the ordering is repaired afterwards as described in `ValidatedConstraints::enforce`.
+    pub fn eades_lin_smyth(&self) -> Vec<usize> {
+        let mut incoming_edges: Vec<Vec<(usize, usize)>> = vec![Vec::new(); self.number_of_nodes];
+        for (u, adjacent_nodes) in self.adjacency_list.iter().enumerate() {
+            for (v, weight) in adjacent_nodes {
+                incoming_edges[*v].push((u, *weight));
+            }
+        }
+
+        let mut in_degrees: Vec<usize> = incoming_edges.iter().map(Vec::len).collect();
+        let mut out_degrees: Vec<usize> = self.adjacency_list.iter().map(HashMap::len).collect();
+        let mut deltas: Vec<isize> = (0..self.number_of_nodes)
+            .map(|node| {
+                let outgoing_weight: usize = self.adjacency_list[node].values().sum();
+                let incoming_weight: usize = incoming_edges[node].iter().map(|(_, weight)| weight).sum();
+                outgoing_weight as isize - incoming_weight as isize
+            })
+            .collect();
+        let mut is_removed = vec![false; self.number_of_nodes];
+
+        let mut sinks: Vec<usize> = (0..self.number_of_nodes).filter(|node| out_degrees[*node] == 0).collect();
+        let mut sources: Vec<usize> = (0..self.number_of_nodes).filter(|node| in_degrees[*node] == 0).collect();
+        // entries become outdated when the delta of a node changes, they are skipped when they are popped
+        let mut candidates: BinaryHeap<(isize, Reverse<usize>)> =
+            (0..self.number_of_nodes).map(|node| (deltas[node], Reverse(node))).collect();
+
+        let mut front = Vec::with_capacity(self.number_of_nodes);
+        let mut back = Vec::new();
+        let mut number_of_removed_nodes = 0;
+
+        while number_of_removed_nodes < self.number_of_nodes {
+            let (node, is_sink) = if let Some(sink) = sinks.pop() {
+                (sink, true)
+            } else if let Some(source) = sources.pop() {
+                (source, false)
+            } else {
+                let (delta, Reverse(node)) = candidates.pop().expect("Some node is not removed yet");
+                if delta != deltas[node] {
+                    continue;
+                }
+                (node, false)
+            };
+            if is_removed[node] {
+                continue;
+            }
+            is_removed[node] = true;
+            number_of_removed_nodes += 1;
+            if is_sink {
+                back.push(node);
+            } else {
+                front.push(node);
+            }
+
+            for (successor, weight) in &self.adjacency_list[node] {
+                if !is_removed[*successor] {
+                    in_degrees[*successor] -= 1;
+                    deltas[*successor] += *weight as isize;
+                    candidates.push((deltas[*successor], Reverse(*successor)));
+                    if in_degrees[*successor] == 0 {
+                        sources.push(*successor);
+                    }
+                }
+            }
+            for (predecessor, weight) in &incoming_edges[node] {
+                if !is_removed[*predecessor] {
+                    out_degrees[*predecessor] -= 1;
+                    deltas[*predecessor] -= *weight as isize;
+                    candidates.push((deltas[*predecessor], Reverse(*predecessor)));
+                    if out_degrees[*predecessor] == 0 {
+                        sinks.push(*predecessor);
+                    }
+                }
+            }
+        }
+
+        front.extend(back.into_iter().rev());
+        self.enforce_constraints(front)
+    }
+
+    /// Computes an ordering with the KwikSort pivot algorithm
+    ///
+    /// The node in the middle of the current range is chosen as the pivot. All nodes with an edge to the pivot are placed in front of it,
+    /// all other nodes behind it (keeping their relative order), and both parts are sorted recursively.
+    /// The pivot is chosen deterministically, so the result only depends on the digraph.
+    ///
+    /// With constraints, the ordering is repaired afterwards as described in `ValidatedConstraints::enforce`.
+    pub fn kwik_sort(&self) -> Vec<usize> {
+        enum Task {
+            Sort(Vec<usize>),
+            Place(usize),
+        }
+
+        let mut ordering = Vec::with_capacity(self.number_of_nodes);
+        // the tasks are processed in reverse order, so the nodes in front of a pivot are pushed last
+        let mut tasks = vec![Task::Sort((0..self.number_of_nodes).collect())];
+
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Place(node) => ordering.push(node),
+                Task::Sort(nodes) if nodes.len() <= 1 => ordering.extend(nodes),
+                Task::Sort(nodes) => {
+                    let pivot = nodes[nodes.len() / 2];
+                    let (before, after): (Vec<usize>, Vec<usize>) = nodes
+                        .into_iter()
+                        .filter(|node| *node != pivot)
+                        .partition(|node| self.edge_exists(*node, pivot));
+
+                    tasks.push(Task::Sort(after));
+                    tasks.push(Task::Place(pivot));
+                    tasks.push(Task::Sort(before));
+                }
+            }
+        }
+
+        self.enforce_constraints(ordering)
+    }
+}
+
+// PRIVATE METHODS
+impl PenaltyDigraph {
+    /// Returns for each of the given nodes the set of forced predecessors among them, as a bit mask over the positions in `nodes`
+    fn forced_predecessor_masks(&self, nodes: &[usize]) -> Vec<u32> {
+        nodes
+            .iter()
+            .map(|node| {
+                nodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, other)| self.is_forced_before(**other, *node))
+                    .fold(0, |mask, (bit, _)| mask | 1 << bit)
+            })
+            .collect()
+    }
+
+    /// Computes an optimal ordering with the subset dynamic program, `forced_predecessors[v]` must be placed in front of `v`
+    ///
+    /// `costs[S]` is the minimum weight of backward edges if exactly the nodes in `S` are placed first.
+    /// Appending node `v` to the nodes in `S` makes all edges from `v` to `S` backward edges.
+    fn compute_exact_ordering(&self, forced_predecessors: &[u32]) -> Vec<usize> {
+        let number_of_nodes = self.number_of_nodes;
+        let successor_masks: Vec<u32> = self
+            .adjacency_list
+            .iter()
+            .map(|adjacent_nodes| adjacent_nodes.keys().fold(0, |mask, node| mask | 1 << node))
+            .collect();
+
+        let mut costs = vec![usize::MAX; 1 << number_of_nodes];
+        let mut last_nodes = vec![0u8; 1 << number_of_nodes];
+        costs[0] = 0;
+
+        for subset in 0..(1u32 << number_of_nodes) {
+            if costs[subset as usize] == usize::MAX {
+                continue;
+            }
+            for node in (0..number_of_nodes).filter(|node| subset & 1 << node == 0) {
+                if forced_predecessors[node] & !subset != 0 {
+                    continue;
+                }
+
+                let mut backward_edges = subset & successor_masks[node];
+                let mut cost = costs[subset as usize];
+                while backward_edges != 0 {
+                    let successor = backward_edges.trailing_zeros() as usize;
+                    cost += self.adjacency_list[node][&successor];
+                    backward_edges &= backward_edges - 1;
+                }
+
+                let extended_subset = (subset | 1 << node) as usize;
+                if cost < costs[extended_subset] {
+                    costs[extended_subset] = cost;
+                    last_nodes[extended_subset] = node as u8;
+                }
+            }
+        }
+
+        let mut ordering = Vec::with_capacity(number_of_nodes);
+        let mut subset = (1usize << number_of_nodes) - 1;
+        while subset != 0 {
+            let node = last_nodes[subset] as usize;
+            ordering.push(node);
+            subset &= !(1 << node);
+        }
+        ordering.reverse();
+        ordering
+    }
+
+    /// Returns the successors of a node in ascending index order
+    fn sorted_successors(&self, node: usize) -> Vec<usize> {
+        let mut successors: Vec<usize> = self.adjacency_list[node].keys().copied().collect();
+        successors.sort_unstable();
+        successors
+    }
+
+    /// Returns an ordering that satisfies the constraints of this digraph (or the given ordering, if there are none)
+    fn enforce_constraints(&self, ordering: Vec<usize>) -> Vec<usize> {
+        match &self.constraints {
+            Some(constraints) => constraints.enforce(&ordering),
+            None => ordering,
+        }
+    }
+
+    /// Checks, if the constraints require `u` to be ordered directly before `v`
+    fn is_forced_before(&self, u: usize, v: usize) -> bool {
+        self.constraints
+            .as_ref()
+            .is_some_and(|constraints| constraints.predecessors(v).contains(&u))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn permutations(n: usize) -> Vec<Vec<usize>> {
+        if n == 0 {
+            return vec![Vec::new()];
+        }
+
+        let mut extended_permutations = Vec::new();
+        for permutation in permutations(n - 1) {
+            for position in 0..=permutation.len() {
+                let mut permutation = permutation.clone();
+                permutation.insert(position, n - 1);
+                extended_permutations.push(permutation);
+            }
+        }
+        extended_permutations
+    }
+
+    /// Constructs a digraph with at most one weighted edge between each pair of nodes, like a penalty digraph
+    fn random_digraph(number_of_nodes: usize, rng: &mut impl Rng) -> PenaltyDigraph {
+        let mut penalty_digraph = PenaltyDigraph::new(number_of_nodes);
+        for u in 0..number_of_nodes {
+            for v in u + 1..number_of_nodes {
+                match rng.gen_range(0..3) {
+                    0 => {}
+                    1 => {
+                        penalty_digraph.add_edge(u, v, rng.gen_range(1..5));
+                    }
+                    _ => {
+                        penalty_digraph.add_edge(v, u, rng.gen_range(1..5));
+                    }
+                }
+            }
+        }
+        penalty_digraph
+    }
+
+    fn feedback_weight(penalty_digraph: &PenaltyDigraph, ordering: &[usize]) -> usize {
+        penalty_digraph
+            .feedback_arcs(ordering)
+            .unwrap()
+            .iter()
+            .map(|(_, _, weight)| weight)
+            .sum()
+    }
+
+    /// Returns the minimum feedback weight over all orderings that satisfy the constraints of the digraph
+    fn exhaustive_minimum(penalty_digraph: &PenaltyDigraph) -> usize {
+        permutations(penalty_digraph.number_of_nodes())
+            .iter()
+            .filter(|ordering| {
+                penalty_digraph
+                    .constraints()
+                    .is_none_or(|constraints| constraints.is_satisfied_by(ordering))
+            })
+            .map(|ordering| feedback_weight(penalty_digraph, ordering))
+            .min()
+            .expect("There is at least one valid ordering")
+    }
+
+    #[test]
+    fn exact_fas_is_optimal() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for number_of_nodes in 0..=7 {
+            for _ in 0..5 {
+                let penalty_digraph = random_digraph(number_of_nodes, &mut rng);
+                let ordering = penalty_digraph.exact_fas().unwrap();
+
+                assert_eq!(
+                    feedback_weight(&penalty_digraph, &ordering),
+                    exhaustive_minimum(&penalty_digraph)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn exact_fas_minimizes_the_crossings_of_a_graph() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for number_of_free_nodes in 1..=6 {
+            let mut graph = Graph::new(4, number_of_free_nodes);
+            for fixed_node_index in 0..4 {
+                for free_node_index in graph.free_nodes() {
+                    if rng.gen_bool(0.4) {
+                        graph
+                            .add_weighted_edge(fixed_node_index, free_node_index, rng.gen_range(1..3))
+                            .unwrap();
+                    }
+                }
+            }
+            let to_indices = |ordering: &[usize]| -> Vec<usize> { ordering.iter().map(|node| node + 4).collect() };
+
+            let ordering = to_indices(&PenaltyDigraph::from_graph(&graph).exact_fas().unwrap());
+            let minimum = permutations(number_of_free_nodes)
+                .iter()
+                .map(|ordering| graph.compute_number_of_crossings_for_ordering(&to_indices(ordering)).unwrap())
+                .min()
+                .unwrap();
+
+            assert_eq!(graph.compute_number_of_crossings_for_ordering(&ordering).unwrap(), minimum);
+        }
+    }
+
+    #[test]
+    fn exact_fas_is_optimal_among_orderings_that_respect_the_precedences() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..10 {
+            let mut graph = Graph::new(5, 6);
+            for fixed_node_index in 0..5 {
+                for free_node_index in graph.free_nodes() {
+                    if rng.gen_bool(0.5) {
+                        graph.add_edge(fixed_node_index, free_node_index).unwrap();
+                    }
+                }
+            }
+            let mut constraints = OrderingConstraints::new();
+            let free = |offset: usize| graph.number_of_fixed_nodes() + offset;
+            constraints.add_precedence(free(3), free(1));
+            constraints.add_precedence(free(1), free(4));
+            constraints.add_precedence(free(0), free(2));
+            let penalty_digraph = PenaltyDigraph::from_graph_with_constraints(&graph, &constraints).unwrap();
+
+            let ordering = penalty_digraph.exact_fas().unwrap();
+
+            assert!(penalty_digraph.constraints().unwrap().is_satisfied_by(&ordering));
+            assert_eq!(
+                feedback_weight(&penalty_digraph, &ordering),
+                exhaustive_minimum(&penalty_digraph)
+            );
+        }
+    }
+
+    #[test]
+    fn fas_by_components_with_exact_threshold_is_optimal() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for number_of_nodes in 1..=7 {
+            for _ in 0..5 {
+                let penalty_digraph = random_digraph(number_of_nodes, &mut rng);
+                let minimum = exhaustive_minimum(&penalty_digraph);
+
+                for method in FasMethod::ALL {
+                    let ordering = penalty_digraph.compute_fas_by_components(method, MAX_EXACT_FAS_NODES);
+                    assert_eq!(feedback_weight(&penalty_digraph, &ordering), minimum);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fas_by_components_returns_a_permutation() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let penalty_digraph = random_digraph(30, &mut rng);
+
+        for method in FasMethod::ALL {
+            for exact_threshold in [0, 5, usize::MAX] {
+                let mut ordering = penalty_digraph.compute_fas_by_components(method, exact_threshold);
+                ordering.sort_unstable();
+                assert_eq!(ordering, (0..30).collect::<Vec<usize>>());
+            }
+        }
+    }
+
+    #[test]
+    fn exact_fas_rejects_too_large_digraphs() {
+        let penalty_digraph = PenaltyDigraph::new(MAX_EXACT_FAS_NODES + 1);
+
+        assert!(matches!(penalty_digraph.exact_fas(), Err(Error::ValueError(_))));
+    }
+
+    #[test]
+    fn fas_by_components_caps_the_exact_threshold() {
+        // A single cycle is one strongly connected component that is too large for the exact computation
+        let number_of_nodes = MAX_EXACT_FAS_NODES + 5;
+        let mut penalty_digraph = PenaltyDigraph::new(number_of_nodes);
+        for u in 0..number_of_nodes {
+            penalty_digraph.add_edge(u, (u + 1) % number_of_nodes, 1);
+        }
+
+        for method in FasMethod::ALL {
+            let mut ordering = penalty_digraph.compute_fas_by_components(method, usize::MAX);
+            ordering.sort_unstable();
+            assert_eq!(ordering, (0..number_of_nodes).collect::<Vec<usize>>());
+        }
+    }
+
+    fn digraph_with_edges(number_of_nodes: usize, edges: &[(usize, usize)]) -> PenaltyDigraph {
+        let mut penalty_digraph = PenaltyDigraph::new(number_of_nodes);
+        for (u, v) in edges {
+            penalty_digraph.add_edge(*u, *v, 1);
+        }
+        penalty_digraph
+    }
+
+    /// Asserts that the components partition the nodes and every edge between two components points to a later one
+    fn assert_topologically_ordered(penalty_digraph: &PenaltyDigraph, components: &[Vec<usize>]) {
+        let mut component_of = vec![None; penalty_digraph.number_of_nodes()];
+        for (component_index, component) in components.iter().enumerate() {
+            for node in component {
+                assert_eq!(component_of[*node], None, "The node {node} is in more than one component");
+                component_of[*node] = Some(component_index);
+            }
+        }
+        assert!(component_of.iter().all(Option::is_some));
+
+        for (u, v, _) in penalty_digraph.edges() {
+            assert!(component_of[u] <= component_of[v], "The edge {u} -> {v} points backwards");
+        }
+    }
+
+    #[test]
+    fn every_node_of_a_dag_is_its_own_component() {
+        let penalty_digraph = digraph_with_edges(5, &[(3, 1), (1, 4), (3, 0), (0, 4), (2, 0)]);
+
+        let components = penalty_digraph.strongly_connected_components();
+
+        assert_eq!(components.len(), 5);
+        assert!(components.iter().all(|component| component.len() == 1));
+        assert_topologically_ordered(&penalty_digraph, &components);
+    }
+
+    #[test]
+    fn a_single_cycle_is_one_component() {
+        let penalty_digraph = digraph_with_edges(4, &[(0, 2), (2, 1), (1, 3), (3, 0)]);
+
+        assert_eq!(penalty_digraph.strongly_connected_components(), vec![vec![0, 1, 2, 3]]);
+    }
+
+    #[test]
+    fn two_cycles_joined_by_one_edge_are_ordered_topologically() {
+        // The cycle {1, 3, 4} has an edge to the cycle {0, 2}
+        let penalty_digraph = digraph_with_edges(5, &[(0, 2), (2, 0), (1, 3), (3, 4), (4, 1), (4, 2)]);
+
+        assert_eq!(
+            penalty_digraph.strongly_connected_components(),
+            vec![vec![1, 3, 4], vec![0, 2]]
+        );
+    }
+
+    #[test]
+    fn components_of_random_digraphs_are_strongly_connected_and_ordered() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for number_of_nodes in 0..12 {
+            let mut penalty_digraph = PenaltyDigraph::new(number_of_nodes);
+            for u in 0..number_of_nodes {
+                for v in 0..number_of_nodes {
+                    if u != v && rng.gen_bool(0.15) {
+                        penalty_digraph.add_edge(u, v, 1);
+                    }
+                }
+            }
+
+            // Transitive closure of the reachability, every node reaches itself
+            let mut is_reachable = vec![vec![false; number_of_nodes]; number_of_nodes];
+            for (u, row) in is_reachable.iter_mut().enumerate() {
+                row[u] = true;
+            }
+            for (u, v, _) in penalty_digraph.edges() {
+                is_reachable[u][v] = true;
+            }
+            for w in 0..number_of_nodes {
+                for u in 0..number_of_nodes {
+                    for v in 0..number_of_nodes {
+                        if is_reachable[u][w] && is_reachable[w][v] {
+                            is_reachable[u][v] = true;
+                        }
+                    }
+                }
+            }
+
+            let components = penalty_digraph.strongly_connected_components();
+            assert_topologically_ordered(&penalty_digraph, &components);
+            let mut component_of = vec![0; number_of_nodes];
+            for (component_index, component) in components.iter().enumerate() {
+                for node in component {
+                    component_of[*node] = component_index;
+                }
+            }
+            for u in 0..number_of_nodes {
+                for v in 0..number_of_nodes {
+                    let is_strongly_connected = is_reachable[u][v] && is_reachable[v][u];
+                    assert_eq!(component_of[u] == component_of[v], is_strongly_connected);
+                }
+            }
+        }
     }
 }
@@ -1,80 +1,117 @@
 use std::{
     fs::File,
     io::{BufRead, BufReader},
+    str::FromStr,
 };
 
-use rand::{seq::SliceRandom, thread_rng, Rng};
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    seq::{index, SliceRandom},
+    thread_rng, Rng,
+};
 
-use crate::{error::Error, graph::Graph};
+use crate::{
+    error::{Error, ParseErrorKind},
+    graph::Graph,
+};
 
 /// Helper struct that constructs Graphs in different ways
 pub struct GraphBuilder {}
 
+/// A graph together with an ordering of its free nodes that is known to be optimal
+#[derive(Debug)]
+pub struct PlantedGraph {
+    pub graph: Graph,
+    pub optimal_ordering: Vec<usize>,
+    pub optimal_number_of_crossings: usize,
+}
+
 // PUBLIC METHODS ------------------------------------------------------------------
 impl GraphBuilder {
     /// Constructs a random graph, that suits the description in `Application::run_on_specific_case`
-    pub fn build_graph_with_fixed_nodes_and_no_crossings(number_of_fixed_nodes: usize) -> Graph {
+    pub fn build_graph_with_fixed_nodes_and_no_crossings(number_of_fixed_nodes: usize) -> Result<Graph, Error> {
+        if number_of_fixed_nodes == 0 {
+            return Err(Error::ValueError(
+                "The graph needs at least one fixed node".to_string(),
+            ));
+        }
         let mut graph = Graph::new(number_of_fixed_nodes, number_of_fixed_nodes);
 
         let mut randomly_ordered_free_nodes: Vec<usize> =
             (number_of_fixed_nodes..2 * number_of_fixed_nodes).collect();
         randomly_ordered_free_nodes.shuffle(&mut thread_rng());
 
-        for fixed_node_index in 0..number_of_fixed_nodes - 1 {
-            let neighbor1 = randomly_ordered_free_nodes
-                .get(fixed_node_index)
-                .expect("Must exist")
-                .to_owned();
-            let neighbor2 = randomly_ordered_free_nodes
-                .get(fixed_node_index + 1)
-                .expect("Must exist")
-                .to_owned();
-            graph.add_edge(neighbor1, fixed_node_index).unwrap();
-            graph.add_edge(neighbor2, fixed_node_index).unwrap();
+        for (fixed_node_index, neighbors) in randomly_ordered_free_nodes.windows(2).enumerate() {
+            graph.add_edge(neighbors[0], fixed_node_index)?;
+            graph.add_edge(neighbors[1], fixed_node_index)?;
         }
 
-        graph
-            .add_edge(
-                randomly_ordered_free_nodes.last().unwrap().to_owned(),
-                number_of_fixed_nodes - 1,
-            )
-            .unwrap();
+        graph.add_edge(
+            randomly_ordered_free_nodes[number_of_fixed_nodes - 1],
+            number_of_fixed_nodes - 1,
+        )?;
 
-        graph
+        Ok(graph)
     }
 
     /// Constructs a Graph from a pace-formatted .gr file
+    ///
+    /// Edge lines may contain an optional third column with the weight of the edge.
     pub fn build_graph_from_file(filename: &str) -> Result<Graph, Error> {
-        let file = File::open(filename)?;
-
-        let mut lines = BufReader::new(file).lines().flatten();
-        let p_line = lines
-            .find(|line| line.starts_with('p'))
-            .ok_or(Error::ParseError(
-                "Could not find a valid p line in the file".to_string(),
-            ))?;
-
-        let p_line_info = PLineInfo::build(&p_line).ok_or(Error::ParseError(
-            "The found p-line was invalid".to_string(),
-        ))?;
+        let file = File::open(filename).map_err(Error::with_path(filename))?;
+
+        let mut lines = BufReader::new(file).lines().enumerate();
+        let mut p_line = None;
+        let mut number_of_lines = 0;
+        for (line_index, line) in lines.by_ref() {
+            let line = line?;
+            number_of_lines = line_index + 1;
+            if line.starts_with('p') {
+                p_line = Some((line_index + 1, line));
+                break;
+            }
+        }
+        let (p_line_number, p_line) = p_line.ok_or(Error::ParseError {
+            line: number_of_lines + 1,
+            column: 1,
+            kind: ParseErrorKind::MissingPLine,
+        })?;
+        let p_line_info = PLineInfo::build(&p_line, p_line_number)?;
 
         let mut graph = Graph::new(
             p_line_info.number_of_fixed_nodes,
             p_line_info.number_of_free_nodes,
         );
 
-        for line in lines {
+        for (line_index, line) in lines {
+            let line = line?;
             if line.starts_with('c') || line.is_empty() {
                 continue;
             }
-            let edge = GraphBuilder::parse_edge_line(&line)
-                .ok_or(Error::ParseError("Unexpected line found!".to_string()))?;
-
-            graph.add_edge(edge.0 - 1, edge.1 - 1)?;
+            let (fixed_node, free_node, weight) = GraphBuilder::parse_edge_line(&line, line_index + 1)?;
+
+            let invalid_edge = |reason: String| Error::ParseError {
+                line: line_index + 1,
+                column: 1,
+                kind: ParseErrorKind::InvalidEdge(reason),
+            };
+            if fixed_node == 0 || free_node == 0 {
+                return Err(invalid_edge("Node labels start at 1".to_string()));
+            }
+            graph
+                .add_weighted_edge(fixed_node - 1, free_node - 1, weight)
+                .map_err(|error| invalid_edge(error.to_string()))?;
         }
 
         if p_line_info.number_of_edges != graph.number_of_edges() {
-            return Err(Error::ParseError(format!("The number of edges in the file was invalid. {} were expected, but {} were actually found.", p_line_info.number_of_edges, graph.number_of_edges())));
+            return Err(Error::ParseError {
+                line: p_line_number,
+                column: p_line_info.number_of_edges_column,
+                kind: ParseErrorKind::WrongNumberOfEdges {
+                    expected: p_line_info.number_of_edges,
+                    found: graph.number_of_edges(),
+                },
+            });
         }
 
         Ok(graph)
@@ -85,6 +122,21 @@ impl GraphBuilder {
         number_of_fixed_nodes: usize,
         number_of_free_nodes: usize,
         number_of_edges: usize,
+    ) -> Result<Graph, Error> {
+        GraphBuilder::build_random_graph_with_rng(
+            number_of_fixed_nodes,
+            number_of_free_nodes,
+            number_of_edges,
+            &mut thread_rng(),
+        )
+    }
+
+    /// Constructs a random graph with the given random number generator, so that the graph can be reproduced from a seed
+    pub fn build_random_graph_with_rng(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        number_of_edges: usize,
+        rng: &mut impl Rng,
     ) -> Result<Graph, Error> {
         if let Some(maximum_number_of_edges) =
             number_of_fixed_nodes.checked_mul(number_of_free_nodes)
@@ -97,7 +149,6 @@ impl GraphBuilder {
         }
 
         let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
-        let mut rng = rand::thread_rng();
 
         for _ in 0..number_of_edges {
             loop {
@@ -115,62 +166,530 @@ impl GraphBuilder {
     }
 }
 
-// PRIVATE METHODS ------------------------------------------------------------------
+// STRUCTURED GRAPHS ------------------------------------------------------------------
+//
+// The following graphs mirror the families in `ressources/tiny_test_set`.
+// The free nodes are numbered "sorted", i.e. in the order that a human would draw them.
+// Use `GraphBuilder::shuffle_free_nodes` to obtain a "shuffled" variant.
 impl GraphBuilder {
-    fn parse_edge_line(line: &str) -> Option<(usize, usize)> {
-        let words: Vec<&str> = line.split(' ').collect();
+    /// Constructs a path with `number_of_nodes` nodes that alternates between fixed and free nodes
+    ///
+    /// The path starts (and, for an odd number of nodes, also ends) with a fixed node.
+    /// The default ordering has no crossings.
+    pub fn build_path_graph(number_of_nodes: usize) -> Result<Graph, Error> {
+        let number_of_fixed_nodes = number_of_nodes.div_ceil(2);
+        let number_of_free_nodes = number_of_nodes / 2;
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+
+        for free_node_offset in 0..number_of_free_nodes {
+            let free_node_index = number_of_fixed_nodes + free_node_offset;
+            graph.add_edge(free_node_offset, free_node_index)?;
+            if free_node_offset + 1 < number_of_fixed_nodes {
+                graph.add_edge(free_node_offset + 1, free_node_index)?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a cycle with `2 * half_length` nodes that alternates between fixed and free nodes
+    ///
+    /// Every drawing of a cycle has at least one crossing.
+    pub fn build_cycle_graph(half_length: usize) -> Result<Graph, Error> {
+        if half_length < 2 {
+            return Err(Error::ValueError(
+                "A cycle needs at least 2 fixed and 2 free nodes".to_string(),
+            ));
+        }
+
+        let mut graph = Graph::new(half_length, half_length);
+        for offset in 0..half_length {
+            graph.add_edge(offset, half_length + offset)?;
+            graph.add_edge((offset + 1) % half_length, half_length + offset)?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a ladder, i.e. a grid with 2 rows and `length` columns
+    pub fn build_ladder_graph(length: usize) -> Result<Graph, Error> {
+        GraphBuilder::build_grid_graph(2, length)
+    }
+
+    /// Constructs a grid with `number_of_rows` rows and `number_of_columns` columns
+    ///
+    /// The grid is 2-colored like a chess board, the cells with an even coordinate sum become fixed nodes.
+    /// Both layers are numbered column by column.
+    pub fn build_grid_graph(number_of_rows: usize, number_of_columns: usize) -> Result<Graph, Error> {
+        let number_of_cells = number_of_rows * number_of_columns;
+        let number_of_fixed_nodes = number_of_cells.div_ceil(2);
+        let number_of_free_nodes = number_of_cells / 2;
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+
+        // The cells `2k` and `2k + 1` (column by column) always have different colors,
+        // so a cell is the (cell / 2)-th node of its color class.
+        let node_index = |row: usize, column: usize| {
+            let cell = column * number_of_rows + row;
+            if (row + column).is_multiple_of(2) {
+                cell / 2
+            } else {
+                number_of_fixed_nodes + cell / 2
+            }
+        };
+
+        for column in 0..number_of_columns {
+            for row in 0..number_of_rows {
+                if row + 1 < number_of_rows {
+                    graph.add_edge(node_index(row, column), node_index(row + 1, column))?;
+                }
+                if column + 1 < number_of_columns {
+                    graph.add_edge(node_index(row, column), node_index(row, column + 1))?;
+                }
+            }
+        }
 
-        if words.len() != 2 {
-            return None;
+        Ok(graph)
+    }
+
+    /// Constructs `number_of_centers` disjoint stars with `leaves_per_center` leaves each
+    ///
+    /// The centers are the fixed nodes. The default ordering has no crossings.
+    pub fn build_star_graph(number_of_centers: usize, leaves_per_center: usize) -> Result<Graph, Error> {
+        let number_of_free_nodes = number_of_centers * leaves_per_center;
+        let mut graph = Graph::new(number_of_centers, number_of_free_nodes);
+
+        for center in 0..number_of_centers {
+            for leaf in 0..leaves_per_center {
+                graph.add_edge(center, number_of_centers + center * leaves_per_center + leaf)?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a complete tree in which every inner node has `branching_factor` children
+    ///
+    /// The levels `0..=depth` alternate between fixed (even levels) and free nodes (odd levels).
+    /// Both layers are numbered in breadth-first order.
+    pub fn build_tree_graph(branching_factor: usize, depth: usize) -> Result<Graph, Error> {
+        let mut level_sizes = vec![1usize];
+        let mut level_size = 1usize;
+        for _ in 0..depth {
+            level_size = level_size
+                .checked_mul(branching_factor)
+                .ok_or(Error::ValueError("The tree is too large".to_string()))?;
+            level_sizes.push(level_size);
+        }
+
+        let number_of_fixed_nodes: usize = level_sizes.iter().step_by(2).sum();
+        let number_of_free_nodes: usize = level_sizes.iter().skip(1).step_by(2).sum();
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+
+        // first index of the current level and of the level above it, for even and odd levels separately
+        let mut next_index = [0, number_of_fixed_nodes];
+        let mut parent_level_start = 0;
+        for (level, level_size) in level_sizes.iter().enumerate() {
+            let level_start = next_index[level % 2];
+            if level > 0 {
+                for node in 0..*level_size {
+                    graph.add_edge(parent_level_start + node / branching_factor, level_start + node)?;
+                }
+            }
+            next_index[level % 2] += level_size;
+            parent_level_start = level_start;
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs the complete bipartite graph between all fixed and all free nodes
+    ///
+    /// Every ordering has exactly `(fixed choose 2) * (free choose 2)` crossings.
+    pub fn build_complete_bipartite_graph(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+    ) -> Result<Graph, Error> {
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+
+        for fixed_node_index in 0..number_of_fixed_nodes {
+            for free_node_index in number_of_fixed_nodes..graph.number_of_nodes() {
+                graph.add_edge(fixed_node_index, free_node_index)?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a perfect matching between `number_of_pairs` fixed and free nodes
+    ///
+    /// The default ordering has no crossings.
+    pub fn build_matching_graph(number_of_pairs: usize) -> Result<Graph, Error> {
+        let mut graph = Graph::new(number_of_pairs, number_of_pairs);
+
+        for offset in 0..number_of_pairs {
+            graph.add_edge(offset, number_of_pairs + offset)?;
         }
 
-        let fixed_option = words.first().unwrap().parse::<usize>().ok();
-        let free_option = words.get(1).unwrap().parse::<usize>().ok();
+        Ok(graph)
+    }
+
+    /// Constructs a copy of the graph with randomly renamed free nodes
+    pub fn shuffle_free_nodes(graph: &Graph, rng: &mut impl Rng) -> Result<Graph, Error> {
+        let permutation = GraphBuilder::random_free_node_permutation(graph, rng);
+        graph.relabel_free_nodes(&permutation)
+    }
 
-        if fixed_option.is_none() || free_option.is_none() {
-            return None;
+    /// Constructs a random graph whose optimal number of crossings is known
+    ///
+    /// The graph consists of `number_of_blocks` complete bipartite blocks with 1 to `maximum_block_size` nodes on each side.
+    /// Each block is connected to the next one by an edge from its last free node to the first fixed node of the next block.
+    /// Every ordering has at least the crossings inside of the blocks, and ordering the free nodes block by block
+    /// adds no further crossings, so that ordering is optimal.
+    /// The free nodes are shuffled, the returned `optimal_ordering` refers to the shuffled names.
+    pub fn build_planted_graph(
+        number_of_blocks: usize,
+        maximum_block_size: usize,
+        rng: &mut impl Rng,
+    ) -> Result<PlantedGraph, Error> {
+        if maximum_block_size == 0 {
+            return Err(Error::ValueError(
+                "The blocks need at least one node on each side".to_string(),
+            ));
         }
 
-        Some((fixed_option.unwrap(), free_option.unwrap()))
+        let block_sizes: Vec<(usize, usize)> = (0..number_of_blocks)
+            .map(|_| {
+                (
+                    rng.gen_range(1..=maximum_block_size),
+                    rng.gen_range(1..=maximum_block_size),
+                )
+            })
+            .collect();
+        let number_of_fixed_nodes = block_sizes.iter().map(|(fixed, _)| fixed).sum();
+        let number_of_free_nodes: usize = block_sizes.iter().map(|(_, free)| free).sum();
+
+        let mut sorted_graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+        let mut optimal_number_of_crossings = 0;
+        let mut fixed_start = 0;
+        let mut free_start = number_of_fixed_nodes;
+        for (fixed_block_size, free_block_size) in block_sizes {
+            if fixed_start > 0 {
+                sorted_graph.add_edge(fixed_start, free_start - 1)?;
+            }
+            for fixed_node_index in fixed_start..fixed_start + fixed_block_size {
+                for free_node_index in free_start..free_start + free_block_size {
+                    sorted_graph.add_edge(fixed_node_index, free_node_index)?;
+                }
+            }
+            optimal_number_of_crossings += fixed_block_size * (fixed_block_size - 1) / 2
+                * (free_block_size * (free_block_size - 1) / 2);
+            fixed_start += fixed_block_size;
+            free_start += free_block_size;
+        }
+
+        let permutation = GraphBuilder::random_free_node_permutation(&sorted_graph, rng);
+        let graph = sorted_graph.relabel_free_nodes(&permutation)?;
+
+        Ok(PlantedGraph {
+            graph,
+            optimal_ordering: permutation,
+            optimal_number_of_crossings,
+        })
+    }
+}
+
+// DEGREE DISTRIBUTIONS ------------------------------------------------------------------
+//
+// Unlike `GraphBuilder::build_random_graph`, the following graphs do not pick the endpoints of the edges uniformly.
+impl GraphBuilder {
+    /// Constructs a random graph whose degrees in both layers follow a power law
+    ///
+    /// Every node gets a weight `rank^(-1 / (exponent - 1))`, where the ranks are assigned randomly,
+    /// and both endpoints of each edge are picked with probability proportional to their weight (Chung-Lu model).
+    /// The expected number of nodes with degree `k` is then proportional to `k^(-exponent)`.
+    pub fn build_power_law_graph(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        number_of_edges: usize,
+        exponent: f64,
+        rng: &mut impl Rng,
+    ) -> Result<Graph, Error> {
+        if exponent <= 1.0 {
+            return Err(Error::ValueError(
+                "The exponent of a power law must be greater than 1".to_string(),
+            ));
+        }
+        if number_of_edges > number_of_fixed_nodes.saturating_mul(number_of_free_nodes) {
+            return Err(Error::ValueError(
+                "It is not possible to construct the graph with that many edges".to_string(),
+            ));
+        }
+
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+        if number_of_edges == 0 {
+            return Ok(graph);
+        }
+
+        let fixed_node_distribution =
+            GraphBuilder::power_law_distribution(number_of_fixed_nodes, exponent, rng)?;
+        let free_node_distribution =
+            GraphBuilder::power_law_distribution(number_of_free_nodes, exponent, rng)?;
+
+        // Heavy nodes quickly run out of new neighbors, so the number of attempts is bounded.
+        let maximum_number_of_attempts = 100 * number_of_edges;
+        let mut number_of_attempts = 0;
+        while graph.number_of_edges() < number_of_edges {
+            if number_of_attempts == maximum_number_of_attempts {
+                return Err(Error::ValueError(
+                    "Could not place all edges, the power law is too steep for that many edges"
+                        .to_string(),
+                ));
+            }
+            number_of_attempts += 1;
+
+            let fixed_node_index = fixed_node_distribution.sample(rng);
+            let free_node_index = number_of_fixed_nodes + free_node_distribution.sample(rng);
+            graph.add_edge(fixed_node_index, free_node_index)?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a random graph in which every free node has exactly `degree` uniformly chosen neighbors
+    pub fn build_graph_with_fixed_degree(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        degree: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Graph, Error> {
+        if degree > number_of_fixed_nodes {
+            return Err(Error::ValueError(
+                "The degree can not be larger than the number of fixed nodes".to_string(),
+            ));
+        }
+
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+        for free_node_index in number_of_fixed_nodes..graph.number_of_nodes() {
+            for fixed_node_index in index::sample(rng, number_of_fixed_nodes, degree) {
+                graph.add_edge(fixed_node_index, free_node_index)?;
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Constructs a random graph in which every free node has `degree` neighbors close to its own position
+    ///
+    /// The free node with offset `i` is centered above the fixed position `(i + 0.5) * fixed / free`.
+    /// The distance of each neighbor to that center is exponentially distributed with mean `spread`.
+    /// With a small spread the optimal ordering is close to the default ordering.
+    pub fn build_geometric_graph(
+        number_of_fixed_nodes: usize,
+        number_of_free_nodes: usize,
+        degree: usize,
+        spread: f64,
+        rng: &mut impl Rng,
+    ) -> Result<Graph, Error> {
+        if degree > number_of_fixed_nodes {
+            return Err(Error::ValueError(
+                "The degree can not be larger than the number of fixed nodes".to_string(),
+            ));
+        }
+        if spread <= 0.0 {
+            return Err(Error::ValueError("The spread must be positive".to_string()));
+        }
+
+        let mut graph = Graph::new(number_of_fixed_nodes, number_of_free_nodes);
+        if degree == 0 {
+            return Ok(graph);
+        }
+
+        let scale = number_of_fixed_nodes as f64 / number_of_free_nodes as f64;
+        let last_position = number_of_fixed_nodes as f64 - 1.0;
+
+        for free_node_offset in 0..number_of_free_nodes {
+            let free_node_index = number_of_fixed_nodes + free_node_offset;
+            let center = ((free_node_offset as f64 + 0.5) * scale - 0.5).clamp(0.0, last_position);
+
+            let mut degree_of_free_node = 0;
+            for _ in 0..32 * degree {
+                if degree_of_free_node == degree {
+                    break;
+                }
+                let distance = -spread * (1.0 - rng.gen::<f64>()).ln();
+                let signed_distance = if rng.gen_bool(0.5) { distance } else { -distance };
+                let fixed_node_index =
+                    (center + signed_distance).round().clamp(0.0, last_position) as usize;
+                if graph.add_edge(fixed_node_index, free_node_index)? {
+                    degree_of_free_node += 1;
+                }
+            }
+
+            // If the sampling keeps hitting existing neighbors, take the closest remaining fixed nodes instead.
+            let center = center.round() as usize;
+            let mut distance = 0;
+            while degree_of_free_node < degree {
+                for fixed_node_index in [center.checked_sub(distance), Some(center + distance)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|index| *index < number_of_fixed_nodes)
+                {
+                    if degree_of_free_node < degree
+                        && graph.add_edge(fixed_node_index, free_node_index)?
+                    {
+                        degree_of_free_node += 1;
+                    }
+                }
+                distance += 1;
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+// PRIVATE METHODS ------------------------------------------------------------------
+impl GraphBuilder {
+    fn random_free_node_permutation(graph: &Graph, rng: &mut impl Rng) -> Vec<usize> {
+        let mut permutation: Vec<usize> =
+            (graph.number_of_fixed_nodes()..graph.number_of_nodes()).collect();
+        permutation.shuffle(rng);
+        permutation
+    }
+
+    /// Constructs a distribution over `0..number_of_nodes` with randomly assigned power law weights
+    fn power_law_distribution(
+        number_of_nodes: usize,
+        exponent: f64,
+        rng: &mut impl Rng,
+    ) -> Result<WeightedIndex<f64>, Error> {
+        let mut weights: Vec<f64> = (1..=number_of_nodes)
+            .map(|rank| (rank as f64).powf(-1.0 / (exponent - 1.0)))
+            .collect();
+        weights.shuffle(rng);
+
+        WeightedIndex::new(weights).map_err(|error| Error::ValueError(error.to_string()))
+    }
+
+    /// Parses a line `fixed free [weight]`, the weight defaults to 1
+    fn parse_edge_line(line: &str, line_number: usize) -> Result<(usize, usize, u32), Error> {
+        let words = split_words(line);
+
+        if words.len() != 2 && words.len() != 3 {
+            return Err(Error::ParseError {
+                line: line_number,
+                column: 1,
+                kind: ParseErrorKind::WrongNumberOfWords {
+                    expected: "2 or 3".to_string(),
+                    found: words.len(),
+                },
+            });
+        }
+
+        let fixed_node = parse_number(words[0], line_number)?;
+        let free_node = parse_number(words[1], line_number)?;
+        let weight = match words.get(2) {
+            Some(word) => parse_number(*word, line_number)?,
+            None => 1,
+        };
+
+        Ok((fixed_node, free_node, weight))
     }
 }
 
 struct PLineInfo {
-    descriptor: String,
     number_of_fixed_nodes: usize,
     number_of_free_nodes: usize,
     number_of_edges: usize,
+    number_of_edges_column: usize,
 }
 
 impl PLineInfo {
-    fn build(p_line: &str) -> Option<PLineInfo> {
-        if !p_line.starts_with('p') {
-            return None;
-        }
-
-        let words: Vec<&str> = p_line.split(' ').collect();
+    /// Parses a line `p ocr fixed free edges`
+    fn build(p_line: &str, line_number: usize) -> Result<PLineInfo, Error> {
+        let words = split_words(p_line);
 
         if words.len() != 5 {
-            return None;
+            return Err(Error::ParseError {
+                line: line_number,
+                column: 1,
+                kind: ParseErrorKind::WrongNumberOfWords {
+                    expected: "5".to_string(),
+                    found: words.len(),
+                },
+            });
         }
 
-        let descriptor = words.get(1).unwrap().to_string();
-        let number_of_fixed_nodes = words.get(2).unwrap().parse::<usize>().ok();
-        let number_of_free_nodes = words.get(3).unwrap().parse::<usize>().ok();
-        let number_of_edges = words.get(4).unwrap().parse::<usize>().ok();
+        Ok(PLineInfo {
+            number_of_fixed_nodes: parse_number(words[2], line_number)?,
+            number_of_free_nodes: parse_number(words[3], line_number)?,
+            number_of_edges: parse_number(words[4], line_number)?,
+            number_of_edges_column: words[4].0,
+        })
+    }
+}
 
-        if number_of_fixed_nodes.is_none()
-            || number_of_free_nodes.is_none()
-            || number_of_edges.is_none()
-        {
-            return None;
+/// Splits a line at single spaces, each word is returned together with its (1-based) column
+fn split_words(line: &str) -> Vec<(usize, &str)> {
+    let mut column = 1;
+    line.split(' ')
+        .map(|word| {
+            let word_with_column = (column, word);
+            column += word.chars().count() + 1;
+            word_with_column
+        })
+        .collect()
+}
+
+/// Parses a word of the given line as a number
+fn parse_number<T: FromStr>((column, word): (usize, &str), line_number: usize) -> Result<T, Error> {
+    word.parse().map_err(|_| Error::ParseError {
+        line: line_number,
+        column,
+        kind: ParseErrorKind::InvalidNumber(word.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    /// Writes the content to a temporary file, parses it and removes the file again
+    fn parse(name: &str, content: &str) -> Result<Graph, Error> {
+        let filename = env::temp_dir().join(format!("pace_{}_{name}.gr", process::id()));
+        fs::write(&filename, content).unwrap();
+        let result = GraphBuilder::build_graph_from_file(filename.to_str().unwrap());
+        fs::remove_file(&filename).unwrap();
+        result
+    }
+
+    #[test]
+    fn edges_within_one_layer_are_invalid() {
+        for (name, content) in [
+            ("free_free", "p ocr 2 2 2\n1 3\n3 4\n"),
+            ("fixed_fixed", "p ocr 2 2 1\n1 2\n"),
+            ("self_loop", "p ocr 2 2 1\n3 3\n"),
+        ] {
+            assert!(
+                matches!(
+                    parse(name, content),
+                    Err(Error::ParseError {
+                        kind: ParseErrorKind::InvalidEdge(_),
+                        ..
+                    })
+                ),
+                "{name}"
+            );
         }
+    }
 
-        Some(PLineInfo {
-            descriptor,
-            number_of_fixed_nodes: number_of_fixed_nodes.unwrap(),
-            number_of_free_nodes: number_of_free_nodes.unwrap(),
-            number_of_edges: number_of_edges.unwrap(),
-        })
+    #[test]
+    fn valid_file_is_parsed() {
+        let graph = parse("valid", "p ocr 2 2 2\nc comment\n1 4\n2 3\n").unwrap();
+
+        assert_eq!(graph.number_of_edges(), 2);
+        assert_eq!(graph.compute_number_of_crossings_with_default_ordering().unwrap(), 1);
     }
 }
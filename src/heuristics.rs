@@ -0,0 +1,46 @@
+pub mod iterated_greedy;
+pub mod ordering_evaluator;
+pub mod simulated_annealing;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    graph::{constraints::OrderingConstraints, penalty_digraph::PenaltyDigraph, Graph},
+};
+
+use self::ordering_evaluator::OrderingEvaluator;
+
+/// Best ordering of the free nodes found by a heuristic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeuristicResult {
+    pub ordering: Vec<usize>,
+    pub number_of_crossings: usize,
+    pub initial_number_of_crossings: usize,
+    pub iterations: usize,
+}
+
+/// Constructs an `OrderingEvaluator` that starts from the `sort_fas` ordering of the graph
+///
+/// With constraints, the initial ordering satisfies them and the evaluator only allows moves that keep them satisfied.
+pub(crate) fn evaluator_from_sort_fas<'a>(
+    graph: &'a Graph,
+    constraints: Option<&OrderingConstraints>,
+) -> Result<OrderingEvaluator<'a>, Error> {
+    let penalty_digraph = match constraints {
+        Some(constraints) => PenaltyDigraph::from_graph_with_constraints(graph, constraints)?,
+        None => PenaltyDigraph::from_graph(graph),
+    };
+    let ordering: Vec<usize> = penalty_digraph
+        .sort_fas()
+        .iter()
+        .map(|node| node + graph.number_of_fixed_nodes())
+        .collect();
+
+    let mut evaluator = OrderingEvaluator::new(graph, &ordering)?;
+    if let Some(constraints) = penalty_digraph.constraints() {
+        evaluator.set_constraints(constraints.clone())?;
+    }
+
+    Ok(evaluator)
+}
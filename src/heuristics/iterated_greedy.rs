@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+use super::{evaluator_from_sort_fas, ordering_evaluator::OrderingEvaluator, HeuristicResult};
+use crate::{
+    error::Error,
+    graph::{constraints::OrderingConstraints, Graph},
+};
+
+/// Iterated greedy (ruin and recreate) for the ordering of the free nodes
+///
+/// Starts from the `sort_fas` ordering. In each iteration, `ruin_size` random free nodes are removed from the ordering
+/// and reinserted one after another at the cheapest position among the remaining nodes,
+/// where the cost of a position is given by the pairwise crossing numbers `c_uv` (the same numbers a `PenaltyDigraph` is built from).
+/// The new ordering is accepted if it does not have more crossings than the previous one, otherwise the previous ordering is restored.
+///
+/// The search runs until the time limit is reached.
+#[derive(Debug, Clone)]
+pub struct IteratedGreedy {
+    pub ruin_size: usize,
+    pub time_limit: Duration,
+    pub seed: u64,
+}
+
+impl Default for IteratedGreedy {
+    fn default() -> Self {
+        IteratedGreedy {
+            ruin_size: 8,
+            time_limit: Duration::from_secs(10),
+            seed: 0,
+        }
+    }
+}
+
+// PUBLIC METHODS
+impl IteratedGreedy {
+    /// Runs the iterated greedy search on a graph and returns the best ordering that was found
+    pub fn run(&self, graph: &Graph) -> Result<HeuristicResult, Error> {
+        self.search(evaluator_from_sort_fas(graph, None)?)
+    }
+
+    /// Runs the iterated greedy search on a graph, only considering orderings that satisfy the constraints
+    ///
+    /// Nodes that can not be moved to the end of the ordering (because they are pinned or have forced successors) are never removed.
+    /// Returns an error, if the constraints do not fit the graph or can not be satisfied.
+    pub fn run_with_constraints(
+        &self,
+        graph: &Graph,
+        constraints: &OrderingConstraints,
+    ) -> Result<HeuristicResult, Error> {
+        self.search(evaluator_from_sort_fas(graph, Some(constraints))?)
+    }
+}
+
+// PRIVATE METHODS
+impl IteratedGreedy {
+    fn search(&self, mut evaluator: OrderingEvaluator) -> Result<HeuristicResult, Error> {
+        let start = Instant::now();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let initial_number_of_crossings = evaluator.number_of_crossings();
+        let number_of_free_nodes = evaluator.graph().number_of_free_nodes();
+        let ruin_size = self.ruin_size.min(number_of_free_nodes);
+        let mut iterations = 0;
+
+        if ruin_size == 0 || number_of_free_nodes < 2 {
+            return Ok(HeuristicResult {
+                ordering: evaluator.ordering(),
+                number_of_crossings: initial_number_of_crossings,
+                initial_number_of_crossings,
+                iterations,
+            });
+        }
+
+        let mut current_ordering = evaluator.ordering();
+        let mut current_number_of_crossings = initial_number_of_crossings;
+
+        while start.elapsed() < self.time_limit {
+            iterations += 1;
+            let removed_nodes: Vec<usize> = index::sample(&mut rng, number_of_free_nodes, ruin_size)
+                .iter()
+                .map(|position| current_ordering[position])
+                .collect();
+
+            // Ruin: the removed nodes are moved to the end of the ordering, the remaining nodes keep their relative order.
+            let mut number_of_remaining_nodes = number_of_free_nodes;
+            for node in removed_nodes {
+                let from = evaluator.position_of(node);
+                if evaluator.can_move(from, number_of_free_nodes - 1) {
+                    evaluator.move_node(from, number_of_free_nodes - 1);
+                    number_of_remaining_nodes -= 1;
+                }
+            }
+
+            // Recreate: each removed node is inserted at the cheapest position among the nodes in front of it.
+            while number_of_remaining_nodes < number_of_free_nodes {
+                let from = number_of_remaining_nodes;
+                let (to, _) = evaluator.best_move(from, 0, from);
+                evaluator.move_node(from, to);
+                number_of_remaining_nodes += 1;
+            }
+
+            if evaluator.number_of_crossings() <= current_number_of_crossings {
+                current_number_of_crossings = evaluator.number_of_crossings();
+                current_ordering = evaluator.ordering();
+            } else {
+                evaluator.restore_ordering(&current_ordering, current_number_of_crossings);
+            }
+        }
+
+        Ok(HeuristicResult {
+            ordering: current_ordering,
+            number_of_crossings: current_number_of_crossings,
+            initial_number_of_crossings,
+            iterations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::graph_builder::GraphBuilder;
+
+    #[test]
+    fn result_satisfies_the_constraints_and_matches_a_recount() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for seed in 0..5 {
+            let planted_graph = GraphBuilder::build_planted_graph(6, 4, &mut rng).unwrap();
+            let (graph, optimal_ordering) = (&planted_graph.graph, &planted_graph.optimal_ordering);
+            let mut constraints = OrderingConstraints::new();
+            constraints.add_precedence(optimal_ordering[3], optimal_ordering[1]);
+            constraints.add_precedence(optimal_ordering[1], optimal_ordering[0]);
+            constraints.pin(optimal_ordering[2], 0);
+
+            let iterated_greedy = IteratedGreedy {
+                ruin_size: 3,
+                time_limit: Duration::from_millis(50),
+                seed,
+            };
+            let result = iterated_greedy.run_with_constraints(graph, &constraints).unwrap();
+
+            assert!(result.iterations > 0);
+            assert!(constraints.is_satisfied_by(&result.ordering));
+            assert_eq!(
+                graph.compute_number_of_crossings_for_ordering(&result.ordering).unwrap(),
+                result.number_of_crossings
+            );
+            assert!(result.number_of_crossings <= result.initial_number_of_crossings);
+            assert!(result.number_of_crossings >= planted_graph.optimal_number_of_crossings);
+        }
+    }
+}
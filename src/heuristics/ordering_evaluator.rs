@@ -0,0 +1,430 @@
+use crate::{
+    error::Error,
+    graph::{compact_graph::CompactGraph, constraints::ValidatedConstraints, Graph},
+};
+
+/// Incremental evaluation of the number of crossings while the ordering of the free nodes changes
+///
+/// `crossing_matrix` contains the crossing numbers of all pairs of free nodes (`crossing_matrix[u * n + v]` is `c_uv`, as computed by
+/// `CompactGraph::crossing_numbers`), so it needs quadratic memory in the number of free nodes.
+/// The number of crossings of an ordering is the sum of `c_uv` over all pairs where `u` is ordered before `v`,
+/// so swapping two adjacent nodes changes it by `c_vu - c_uv`, which can be evaluated in `O(1)`.
+///
+/// Internally, the free nodes are identified by their offset (`index - number_of_fixed_nodes`), like in a `PenaltyDigraph`.
+/// The public methods use the node indices of the `Graph`.
+#[derive(Debug)]
+pub struct OrderingEvaluator<'a> {
+    graph: &'a Graph,
+    number_of_free_nodes: usize,
+    ordering: Vec<usize>,
+    positions: Vec<usize>,
+    number_of_crossings: usize,
+    crossing_matrix: Vec<usize>,
+    constraints: Option<ValidatedConstraints>,
+}
+
+// CONSTRUCTORS
+impl<'a> OrderingEvaluator<'a> {
+    /// Constructs a new `OrderingEvaluator` for a graph and an initial ordering of its free nodes
+    ///
+    /// The ordering **must** contain all free nodes (and each exactly once), otherwise the function returns an error
+    pub fn new(graph: &'a Graph, ordering: &[usize]) -> Result<OrderingEvaluator<'a>, Error> {
+        let number_of_crossings = graph.compute_number_of_crossings_for_ordering(ordering)?;
+
+        let compact_graph = CompactGraph::from_graph(graph);
+        let number_of_fixed_nodes = graph.number_of_fixed_nodes();
+        let number_of_free_nodes = graph.number_of_free_nodes();
+        let mut crossing_matrix = vec![0; number_of_free_nodes * number_of_free_nodes];
+        for u in 0..number_of_free_nodes {
+            for v in u + 1..number_of_free_nodes {
+                let (c_uv, c_vu) =
+                    compact_graph.crossing_numbers(u + number_of_fixed_nodes, v + number_of_fixed_nodes);
+                crossing_matrix[u * number_of_free_nodes + v] = c_uv;
+                crossing_matrix[v * number_of_free_nodes + u] = c_vu;
+            }
+        }
+
+        let ordering: Vec<usize> = ordering.iter().map(|node| node - number_of_fixed_nodes).collect();
+        let mut positions = vec![0; number_of_free_nodes];
+        for (position, node) in ordering.iter().enumerate() {
+            positions[*node] = position;
+        }
+
+        Ok(OrderingEvaluator {
+            graph,
+            number_of_free_nodes,
+            ordering,
+            positions,
+            number_of_crossings,
+            crossing_matrix,
+            constraints: None,
+        })
+    }
+}
+
+// GETTERS
+impl<'a> OrderingEvaluator<'a> {
+    pub fn graph(&self) -> &'a Graph {
+        self.graph
+    }
+
+    pub fn number_of_crossings(&self) -> usize {
+        self.number_of_crossings
+    }
+
+    pub fn constraints(&self) -> Option<&ValidatedConstraints> {
+        self.constraints.as_ref()
+    }
+}
+
+// PUBLIC METHODS
+impl OrderingEvaluator<'_> {
+    /// Returns the current ordering of the free nodes
+    pub fn ordering(&self) -> Vec<usize> {
+        self.ordering
+            .iter()
+            .map(|node| node + self.graph.number_of_fixed_nodes())
+            .collect()
+    }
+
+    /// Returns the free node at a position of the current ordering
+    pub fn node_at(&self, position: usize) -> usize {
+        self.ordering[position] + self.graph.number_of_fixed_nodes()
+    }
+
+    /// Returns the position of a free node in the current ordering
+    pub fn position_of(&self, free_node_index: usize) -> usize {
+        self.positions[free_node_index - self.graph.number_of_fixed_nodes()]
+    }
+
+    /// Returns the number of crossings between the edges of two free nodes, if `u` is ordered before `v`
+    pub fn crossing_number(&self, u: usize, v: usize) -> usize {
+        let number_of_fixed_nodes = self.graph.number_of_fixed_nodes();
+        self.pair_crossings(u - number_of_fixed_nodes, v - number_of_fixed_nodes)
+    }
+
+    /// Restricts the moves to those that keep the ordering valid for the given constraints
+    ///
+    /// Returns an error if the current ordering does not satisfy the constraints.
+    pub fn set_constraints(&mut self, constraints: ValidatedConstraints) -> Result<(), Error> {
+        if !constraints.is_satisfied_by(&self.ordering) {
+            return Err(Error::InfeasibleConstraints(
+                "The current ordering does not satisfy the constraints".to_string(),
+            ));
+        }
+
+        self.constraints = Some(constraints);
+        Ok(())
+    }
+
+    /// Replaces the current ordering and recomputes the number of crossings from scratch
+    ///
+    /// The ordering **must** contain all free nodes (and each exactly once) and satisfy the constraints, otherwise the function returns an error
+    pub fn set_ordering(&mut self, ordering: &[usize]) -> Result<(), Error> {
+        let number_of_crossings = self.graph.compute_number_of_crossings_for_ordering(ordering)?;
+        let ordering: Vec<usize> = ordering
+            .iter()
+            .map(|node| node - self.graph.number_of_fixed_nodes())
+            .collect();
+        if self
+            .constraints
+            .as_ref()
+            .is_some_and(|constraints| !constraints.is_satisfied_by(&ordering))
+        {
+            return Err(Error::InfeasibleConstraints(
+                "The ordering does not satisfy the constraints".to_string(),
+            ));
+        }
+
+        for (position, node) in ordering.iter().enumerate() {
+            self.positions[*node] = position;
+        }
+        self.ordering = ordering;
+        self.number_of_crossings = number_of_crossings;
+        Ok(())
+    }
+
+    /// Restores an ordering that was returned by `ordering` earlier, together with its number of crossings, in `O(n)`
+    ///
+    /// Unlike `set_ordering`, neither the number of crossings is recounted nor the ordering checked,
+    /// so it **must** be an earlier state of this evaluator.
+    pub fn restore_ordering(&mut self, ordering: &[usize], number_of_crossings: usize) {
+        debug_assert_eq!(ordering.len(), self.number_of_free_nodes);
+        let number_of_fixed_nodes = self.graph.number_of_fixed_nodes();
+        for (position, node) in ordering.iter().enumerate() {
+            self.ordering[position] = node - number_of_fixed_nodes;
+            self.positions[node - number_of_fixed_nodes] = position;
+        }
+        self.number_of_crossings = number_of_crossings;
+    }
+
+    /// Checks, if swapping the nodes at `position` and `position + 1` is allowed by the constraints
+    pub fn can_swap(&self, position: usize) -> bool {
+        self.constraints
+            .as_ref()
+            .is_none_or(|constraints| constraints.allows_swap(&self.ordering, position))
+    }
+
+    /// Checks, if moving the node at position `from` to position `to` is allowed by the constraints
+    pub fn can_move(&self, from: usize, to: usize) -> bool {
+        self.constraints
+            .as_ref()
+            .is_none_or(|constraints| constraints.allows_move(&self.ordering, from, to))
+    }
+
+    /// Returns the change of the number of crossings if the nodes at `position` and `position + 1` were swapped, in `O(1)`
+    pub fn swap_delta(&self, position: usize) -> isize {
+        let (u, v) = (self.ordering[position], self.ordering[position + 1]);
+        self.pair_crossings(v, u) as isize - self.pair_crossings(u, v) as isize
+    }
+
+    /// Swaps the nodes at `position` and `position + 1` and returns the new number of crossings, in `O(1)`
+    ///
+    /// The swap **must** be allowed by the constraints (see `OrderingEvaluator::can_swap`), this is only checked in debug builds.
+    pub fn swap_adjacent(&mut self, position: usize) -> usize {
+        debug_assert!(self.can_swap(position), "The swap violates the constraints");
+        let delta = self.swap_delta(position);
+        self.ordering.swap(position, position + 1);
+        self.positions[self.ordering[position]] = position;
+        self.positions[self.ordering[position + 1]] = position + 1;
+
+        self.apply_delta(delta)
+    }
+
+    /// Returns the change of the number of crossings if the node at position `from` was moved to position `to`, in `O(|from - to|)`
+    ///
+    /// The nodes between both positions are shifted by one position towards `from`.
+    pub fn move_delta(&self, from: usize, to: usize) -> isize {
+        let node = self.ordering[from];
+
+        if from < to {
+            self.ordering[from + 1..=to]
+                .iter()
+                .map(|other| self.pair_crossings(*other, node) as isize - self.pair_crossings(node, *other) as isize)
+                .sum()
+        } else {
+            self.ordering[to..from]
+                .iter()
+                .map(|other| self.pair_crossings(node, *other) as isize - self.pair_crossings(*other, node) as isize)
+                .sum()
+        }
+    }
+
+    /// Moves the node at position `from` to position `to` and returns the new number of crossings, in `O(|from - to|)`
+    ///
+    /// The move **must** be allowed by the constraints (see `OrderingEvaluator::can_move`), this is only checked in debug builds.
+    pub fn move_node(&mut self, from: usize, to: usize) -> usize {
+        debug_assert!(self.can_move(from, to), "The move violates the constraints");
+        let delta = self.move_delta(from, to);
+        let node = self.ordering.remove(from);
+        self.ordering.insert(to, node);
+        for position in from.min(to)..=from.max(to) {
+            self.positions[self.ordering[position]] = position;
+        }
+
+        self.apply_delta(delta)
+    }
+
+    /// Finds the cheapest position between `min_position` and `max_position` (inclusive) for the node at position `from`
+    ///
+    /// Returns the target position and the change of the number of crossings if the node was moved there, in `O(max_position - min_position)`.
+    /// Only moves that are allowed by the constraints are considered, ties are broken in favor of staying at `from`.
+    pub fn best_move(&self, from: usize, min_position: usize, max_position: usize) -> (usize, isize) {
+        let node = self.ordering[from];
+        let mut best_move = (from, 0);
+
+        let mut delta = 0;
+        for position in (min_position..from).rev() {
+            let other = self.ordering[position];
+            if !self.can_pass(node, other, false) {
+                break;
+            }
+            delta += self.pair_crossings(node, other) as isize - self.pair_crossings(other, node) as isize;
+            if delta < best_move.1 {
+                best_move = (position, delta);
+            }
+        }
+
+        let mut delta = 0;
+        for position in from + 1..=max_position {
+            let other = self.ordering[position];
+            if !self.can_pass(node, other, true) {
+                break;
+            }
+            delta += self.pair_crossings(other, node) as isize - self.pair_crossings(node, other) as isize;
+            if delta < best_move.1 {
+                best_move = (position, delta);
+            }
+        }
+
+        best_move
+    }
+}
+
+// PRIVATE METHODS
+impl OrderingEvaluator<'_> {
+    /// Checks, if the constraints allow `node` to be moved past `other` (towards the end of the ordering if `forward`)
+    fn can_pass(&self, node: usize, other: usize, forward: bool) -> bool {
+        self.constraints.as_ref().is_none_or(|constraints| {
+            let blocking_nodes = if forward {
+                constraints.successors(node)
+            } else {
+                constraints.predecessors(node)
+            };
+
+            constraints.pinned_position(node).is_none()
+                && constraints.pinned_position(other).is_none()
+                && !blocking_nodes.contains(&other)
+        })
+    }
+
+    /// Returns `c_uv` for two free node offsets
+    fn pair_crossings(&self, u: usize, v: usize) -> usize {
+        self.crossing_matrix[u * self.number_of_free_nodes + v]
+    }
+
+    fn apply_delta(&mut self, delta: isize) -> usize {
+        self.number_of_crossings = self
+            .number_of_crossings
+            .checked_add_signed(delta)
+            .expect("The number of crossings can not become negative");
+        self.number_of_crossings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::*;
+    use crate::graph::constraints::OrderingConstraints;
+
+    /// Constructs a random graph with weighted edges and a shuffled ordering of its free nodes
+    fn random_instance(rng: &mut impl Rng) -> (Graph, Vec<usize>) {
+        let number_of_fixed_nodes = rng.gen_range(1..8);
+        let mut graph = Graph::new(number_of_fixed_nodes, rng.gen_range(2..10));
+        for fixed_node_index in graph.fixed_nodes() {
+            for free_node_index in graph.free_nodes() {
+                if rng.gen_bool(0.4) {
+                    graph
+                        .add_weighted_edge(fixed_node_index, free_node_index, rng.gen_range(1..4))
+                        .unwrap();
+                }
+            }
+        }
+
+        let mut ordering: Vec<usize> = graph.free_nodes().collect();
+        ordering.shuffle(rng);
+        (graph, ordering)
+    }
+
+    fn recount(evaluator: &OrderingEvaluator) -> usize {
+        evaluator
+            .graph()
+            .compute_number_of_crossings_for_ordering(&evaluator.ordering())
+            .unwrap()
+    }
+
+    #[test]
+    fn swaps_match_a_recount() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..50 {
+            let (graph, ordering) = random_instance(&mut rng);
+            let mut evaluator = OrderingEvaluator::new(&graph, &ordering).unwrap();
+
+            for _ in 0..20 {
+                let position = rng.gen_range(0..graph.number_of_free_nodes() - 1);
+                let expected = recount(&evaluator).checked_add_signed(evaluator.swap_delta(position)).unwrap();
+
+                assert_eq!(evaluator.swap_adjacent(position), expected);
+                assert_eq!(recount(&evaluator), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn moves_match_a_recount() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            let (graph, ordering) = random_instance(&mut rng);
+            let mut evaluator = OrderingEvaluator::new(&graph, &ordering).unwrap();
+
+            for _ in 0..20 {
+                let from = rng.gen_range(0..graph.number_of_free_nodes());
+                let to = rng.gen_range(0..graph.number_of_free_nodes());
+                let expected = recount(&evaluator).checked_add_signed(evaluator.move_delta(from, to)).unwrap();
+
+                assert_eq!(evaluator.move_node(from, to), expected);
+                assert_eq!(recount(&evaluator), expected);
+                for position in 0..graph.number_of_free_nodes() {
+                    assert_eq!(evaluator.position_of(evaluator.node_at(position)), position);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn best_move_is_the_cheapest_allowed_move() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..50 {
+            let (graph, ordering) = random_instance(&mut rng);
+            let number_of_free_nodes = graph.number_of_free_nodes();
+            // The first free node must stay in front of the last one, the second free node keeps its position
+            let mut constraints = OrderingConstraints::new();
+            let (first, second, last) = (ordering[0], ordering[1], ordering[number_of_free_nodes - 1]);
+            if first != last {
+                constraints.add_precedence(first, last);
+            }
+            constraints.pin(second, 1);
+            let mut evaluator = OrderingEvaluator::new(&graph, &ordering).unwrap();
+            evaluator.set_constraints(constraints.validate(&graph).unwrap()).unwrap();
+
+            for _ in 0..20 {
+                let from = rng.gen_range(0..number_of_free_nodes);
+                let min_position = rng.gen_range(0..=from);
+                let max_position = rng.gen_range(from..number_of_free_nodes);
+                let (to, delta) = evaluator.best_move(from, min_position, max_position);
+
+                assert!(evaluator.can_move(from, to));
+                assert_eq!(delta, evaluator.move_delta(from, to));
+                for position in (min_position..=max_position).filter(|position| evaluator.can_move(from, *position)) {
+                    assert!(evaluator.move_delta(from, position) >= delta);
+                }
+
+                let expected = recount(&evaluator).checked_add_signed(delta).unwrap();
+                assert_eq!(evaluator.move_node(from, to), expected);
+                assert_eq!(recount(&evaluator), expected);
+                assert!(constraints.is_satisfied_by(&evaluator.ordering()));
+            }
+        }
+    }
+
+    #[test]
+    fn restore_ordering_resets_the_ordering_and_the_crossings() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let (graph, ordering) = random_instance(&mut rng);
+        let mut evaluator = OrderingEvaluator::new(&graph, &ordering).unwrap();
+        let number_of_crossings = evaluator.number_of_crossings();
+
+        evaluator.move_node(0, graph.number_of_free_nodes() - 1);
+        evaluator.restore_ordering(&ordering, number_of_crossings);
+
+        assert_eq!(evaluator.ordering(), ordering);
+        assert_eq!(evaluator.number_of_crossings(), recount(&evaluator));
+        assert_eq!(evaluator.position_of(ordering[0]), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "violates the constraints")]
+    #[cfg(debug_assertions)]
+    fn moves_that_violate_the_constraints_are_rejected_in_debug_builds() {
+        let mut graph = Graph::new(1, 3);
+        graph.add_edge(0, 1).unwrap();
+        let mut constraints = OrderingConstraints::new();
+        constraints.pin(2, 1);
+        let mut evaluator = OrderingEvaluator::new(&graph, &[1, 2, 3]).unwrap();
+        evaluator.set_constraints(constraints.validate(&graph).unwrap()).unwrap();
+
+        evaluator.swap_adjacent(0);
+    }
+}
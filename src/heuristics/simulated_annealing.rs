@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{evaluator_from_sort_fas, ordering_evaluator::OrderingEvaluator, HeuristicResult};
+use crate::{
+    error::Error,
+    graph::{constraints::OrderingConstraints, Graph},
+};
+
+/// Number of random moves that are sampled to estimate the initial temperature
+const TEMPERATURE_SAMPLES: usize = 100;
+/// Number of moves between two checks of the time limit
+const TIME_CHECK_INTERVAL: usize = 128;
+
+/// Simulated annealing for the ordering of the free nodes
+///
+/// Starts from the `sort_fas` ordering and repeatedly applies a random move, which is either a swap of two adjacent nodes
+/// or the insertion of a node at another position. Improving moves are always accepted, a move that adds `delta` crossings
+/// is accepted with probability `exp(-delta / temperature)`.
+/// After `moves_per_temperature` moves, the temperature is multiplied by `cooling_factor` (geometric cooling).
+///
+/// The search stops when the temperature falls below `minimum_temperature` or the time limit is reached,
+/// which is checked every `TIME_CHECK_INTERVAL` moves.
+#[derive(Debug, Clone)]
+pub struct SimulatedAnnealing {
+    /// Initial temperature, estimated from the average cost of random worsening moves if `None`
+    pub initial_temperature: Option<f64>,
+    pub minimum_temperature: f64,
+    pub cooling_factor: f64,
+    pub moves_per_temperature: usize,
+    /// Probability of an adjacent swap, all other moves are insertions
+    pub swap_probability: f64,
+    pub time_limit: Duration,
+    pub seed: u64,
+}
+
+impl Default for SimulatedAnnealing {
+    fn default() -> Self {
+        SimulatedAnnealing {
+            initial_temperature: None,
+            minimum_temperature: 0.01,
+            cooling_factor: 0.95,
+            moves_per_temperature: 1_000,
+            swap_probability: 0.5,
+            time_limit: Duration::from_secs(10),
+            seed: 0,
+        }
+    }
+}
+
+// PUBLIC METHODS
+impl SimulatedAnnealing {
+    /// Runs the simulated annealing on a graph and returns the best ordering that was found
+    pub fn run(&self, graph: &Graph) -> Result<HeuristicResult, Error> {
+        self.anneal(evaluator_from_sort_fas(graph, None)?)
+    }
+
+    /// Runs the simulated annealing on a graph, only considering orderings that satisfy the constraints
+    ///
+    /// Returns an error, if the constraints do not fit the graph or can not be satisfied.
+    pub fn run_with_constraints(
+        &self,
+        graph: &Graph,
+        constraints: &OrderingConstraints,
+    ) -> Result<HeuristicResult, Error> {
+        self.anneal(evaluator_from_sort_fas(graph, Some(constraints))?)
+    }
+}
+
+// PRIVATE METHODS
+impl SimulatedAnnealing {
+    fn anneal(&self, mut evaluator: OrderingEvaluator) -> Result<HeuristicResult, Error> {
+        self.validate()?;
+
+        let start = Instant::now();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let initial_number_of_crossings = evaluator.number_of_crossings();
+        let mut best_number_of_crossings = initial_number_of_crossings;
+        let mut best_ordering = evaluator.ordering();
+        let mut iterations = 0;
+
+        let number_of_free_nodes = evaluator.graph().number_of_free_nodes();
+        if number_of_free_nodes < 2 {
+            return Ok(HeuristicResult {
+                ordering: best_ordering,
+                number_of_crossings: best_number_of_crossings,
+                initial_number_of_crossings,
+                iterations,
+            });
+        }
+
+        let mut temperature = match self.initial_temperature {
+            Some(temperature) => temperature,
+            None => self.estimate_initial_temperature(&evaluator, &mut rng),
+        };
+
+        'annealing: while temperature >= self.minimum_temperature && start.elapsed() < self.time_limit {
+            for _ in 0..self.moves_per_temperature {
+                if iterations % TIME_CHECK_INTERVAL == 0 && start.elapsed() >= self.time_limit {
+                    break 'annealing;
+                }
+                iterations += 1;
+                let (from, to) = if rng.gen_bool(self.swap_probability) {
+                    let position = rng.gen_range(0..number_of_free_nodes - 1);
+                    (position, position + 1)
+                } else {
+                    random_move(&mut rng, number_of_free_nodes)
+                };
+                if !evaluator.can_move(from, to) {
+                    continue;
+                }
+
+                let delta = evaluator.move_delta(from, to);
+                if delta <= 0 || rng.gen::<f64>() < (-(delta as f64) / temperature).exp() {
+                    let number_of_crossings = evaluator.move_node(from, to);
+                    if number_of_crossings < best_number_of_crossings {
+                        best_number_of_crossings = number_of_crossings;
+                        best_ordering = evaluator.ordering();
+                    }
+                }
+            }
+            temperature *= self.cooling_factor;
+        }
+
+        Ok(HeuristicResult {
+            ordering: best_ordering,
+            number_of_crossings: best_number_of_crossings,
+            initial_number_of_crossings,
+            iterations,
+        })
+    }
+
+    /// Returns an error if a parameter is out of its valid range
+    fn validate(&self) -> Result<(), Error> {
+        if !(self.cooling_factor > 0.0 && self.cooling_factor < 1.0) {
+            return Err(Error::ValueError(
+                "The cooling factor must be between 0 and 1".to_string(),
+            ));
+        }
+        if self
+            .initial_temperature
+            .is_some_and(|temperature| !temperature.is_finite() || temperature <= 0.0)
+        {
+            return Err(Error::ValueError(
+                "The initial temperature must be positive and finite".to_string(),
+            ));
+        }
+        if self.minimum_temperature.is_nan() || self.minimum_temperature <= 0.0 {
+            return Err(Error::ValueError(
+                "The minimum temperature must be positive".to_string(),
+            ));
+        }
+        if self.moves_per_temperature == 0 {
+            return Err(Error::ValueError(
+                "The number of moves per temperature must be positive".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.swap_probability) {
+            return Err(Error::ValueError(
+                "The swap probability must be between 0 and 1".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the average cost of sampled worsening moves, which are then accepted with a probability of about `1 / e`
+    fn estimate_initial_temperature(&self, evaluator: &OrderingEvaluator, rng: &mut impl Rng) -> f64 {
+        let number_of_free_nodes = evaluator.graph().number_of_free_nodes();
+        let worsening_deltas: Vec<isize> = (0..TEMPERATURE_SAMPLES)
+            .map(|_| {
+                let (from, to) = random_move(rng, number_of_free_nodes);
+                evaluator.move_delta(from, to)
+            })
+            .filter(|delta| *delta > 0)
+            .collect();
+
+        if worsening_deltas.is_empty() {
+            1.0
+        } else {
+            worsening_deltas.iter().sum::<isize>() as f64 / worsening_deltas.len() as f64
+        }
+    }
+}
+
+/// Returns two distinct random positions
+fn random_move(rng: &mut impl Rng, number_of_free_nodes: usize) -> (usize, usize) {
+    let from = rng.gen_range(0..number_of_free_nodes);
+    let mut to = rng.gen_range(0..number_of_free_nodes - 1);
+    if to >= from {
+        to += 1;
+    }
+    (from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::graph_builder::GraphBuilder;
+
+    fn annealing() -> SimulatedAnnealing {
+        SimulatedAnnealing {
+            moves_per_temperature: 200,
+            time_limit: Duration::from_millis(50),
+            ..SimulatedAnnealing::default()
+        }
+    }
+
+    #[test]
+    fn result_satisfies_the_constraints_and_matches_a_recount() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for seed in 0..5 {
+            let planted_graph = GraphBuilder::build_planted_graph(6, 4, &mut rng).unwrap();
+            let (graph, optimal_ordering) = (&planted_graph.graph, &planted_graph.optimal_ordering);
+            let mut constraints = OrderingConstraints::new();
+            constraints.add_precedence(optimal_ordering[2], optimal_ordering[0]);
+            constraints.pin(optimal_ordering[1], graph.number_of_free_nodes() - 1);
+
+            let result = SimulatedAnnealing { seed, ..annealing() }
+                .run_with_constraints(graph, &constraints)
+                .unwrap();
+
+            assert!(constraints.is_satisfied_by(&result.ordering));
+            assert_eq!(
+                graph.compute_number_of_crossings_for_ordering(&result.ordering).unwrap(),
+                result.number_of_crossings
+            );
+            assert!(result.number_of_crossings <= result.initial_number_of_crossings);
+            assert!(result.number_of_crossings >= planted_graph.optimal_number_of_crossings);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        let graph = GraphBuilder::build_planted_graph(2, 3, &mut StdRng::seed_from_u64(1)).unwrap().graph;
+        let invalid_annealings = [
+            SimulatedAnnealing { initial_temperature: Some(-5.0), ..annealing() },
+            SimulatedAnnealing { initial_temperature: Some(f64::NAN), ..annealing() },
+            SimulatedAnnealing { minimum_temperature: 0.0, ..annealing() },
+            SimulatedAnnealing { cooling_factor: 1.0, ..annealing() },
+            SimulatedAnnealing { moves_per_temperature: 0, ..annealing() },
+            SimulatedAnnealing { swap_probability: 1.5, ..annealing() },
+        ];
+
+        for simulated_annealing in invalid_annealings {
+            assert!(
+                matches!(simulated_annealing.run(&graph), Err(Error::ValueError(_))),
+                "{simulated_annealing:?}"
+            );
+        }
+    }
+}
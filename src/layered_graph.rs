@@ -0,0 +1,266 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    error::Error,
+    graph::{penalty_digraph::PenaltyDigraph, Graph},
+};
+
+/// Representation of a graph with `k` layers for Sugiyama-style drawings
+///
+/// The nodes of each layer are numbered from `0` to `layer_sizes[l]` (exclusive), i.e. a node is identified by its layer and its index in that layer.
+/// `orderings` represents the current drawing (`orderings[l][p]` is the node of layer `l` at position `p`).
+/// `edges` represents the edges between adjacent layers (`edges[l]` contains pairs `(node in layer l, node in layer l + 1)`).
+///
+/// The crossings of the drawing are minimized by sweeping over the layers. In each step one layer is treated as the fixed layer
+/// and the adjacent layer is ordered by solving the OCM problem between both layers with a `PenaltyDigraph`.
+#[derive(Debug)]
+pub struct LayeredGraph {
+    layer_sizes: Vec<usize>,
+    orderings: Vec<Vec<usize>>,
+    edges: Vec<BTreeSet<(usize, usize)>>,
+}
+
+/// Direction of a sweep over the layers of a `LayeredGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepDirection {
+    /// Orders layer `1` to `k - 1`, each with the layer above as the fixed layer
+    Down,
+    /// Orders layer `k - 2` to `0`, each with the layer below as the fixed layer
+    Up,
+}
+
+// GETTERS
+impl LayeredGraph {
+    pub fn number_of_layers(&self) -> usize {
+        self.layer_sizes.len()
+    }
+
+    pub fn layer_sizes(&self) -> &[usize] {
+        &self.layer_sizes
+    }
+
+    pub fn number_of_edges(&self) -> usize {
+        self.edges.iter().map(BTreeSet::len).sum()
+    }
+}
+
+// PUBLIC METHODS
+impl LayeredGraph {
+    /// Initializes a new `LayeredGraph` without edges, each layer is ordered in ascending index order
+    pub fn new(layer_sizes: &[usize]) -> LayeredGraph {
+        LayeredGraph {
+            layer_sizes: layer_sizes.to_vec(),
+            orderings: layer_sizes.iter().map(|size| (0..*size).collect()).collect(),
+            edges: vec![BTreeSet::new(); layer_sizes.len().saturating_sub(1)],
+        }
+    }
+
+    /// Adds an edge between `upper_node` in layer `layer` and `lower_node` in layer `layer + 1`
+    ///
+    /// Returns Ok(true) if the edge was inserted successfully.
+    /// Returns Ok(false) if the edge already existed.
+    pub fn add_edge(&mut self, layer: usize, upper_node: usize, lower_node: usize) -> Result<bool, Error> {
+        Error::check_index(layer, 0..self.number_of_layers().saturating_sub(1))?;
+        Error::check_index(upper_node, 0..self.layer_sizes[layer])?;
+        Error::check_index(lower_node, 0..self.layer_sizes[layer + 1])?;
+
+        Ok(self.edges[layer].insert((upper_node, lower_node)))
+    }
+
+    /// Returns the current ordering of a layer
+    pub fn ordering(&self, layer: usize) -> Result<&[usize], Error> {
+        self.orderings
+            .get(layer)
+            .map(Vec::as_slice)
+            .ok_or(Error::IndexError {
+                index: layer,
+                range: 0..self.number_of_layers(),
+            })
+    }
+
+    /// Sets the ordering of a layer
+    ///
+    /// The ordering **must** contain all nodes of the layer (and each exactly once), otherwise the function returns an error
+    pub fn set_ordering(&mut self, layer: usize, ordering: &[usize]) -> Result<(), Error> {
+        let layer_size = *self
+            .layer_sizes
+            .get(layer)
+            .ok_or(Error::IndexError {
+                index: layer,
+                range: 0..self.number_of_layers(),
+            })?;
+        Error::check_ordering(ordering, 0..layer_size)?;
+
+        self.orderings[layer] = ordering.to_vec();
+        Ok(())
+    }
+
+    /// Constructs the bipartite `Graph` between two adjacent layers
+    ///
+    /// The nodes of `fixed_layer` become the fixed nodes (with the same indices and the current ordering of that layer),
+    /// node `i` of `free_layer` becomes the free node `layer_sizes[fixed_layer] + i`.
+    pub fn bipartite_graph(&self, fixed_layer: usize, free_layer: usize) -> Result<Graph, Error> {
+        if fixed_layer.abs_diff(free_layer) != 1 || fixed_layer.max(free_layer) >= self.number_of_layers() {
+            return Err(Error::ValueError(
+                "The layers of a bipartite graph must be adjacent".to_string(),
+            ));
+        }
+
+        let number_of_fixed_nodes = self.layer_sizes[fixed_layer];
+        let mut graph = Graph::new(number_of_fixed_nodes, self.layer_sizes[free_layer]);
+        graph.set_fixed_node_ordering(&self.orderings[fixed_layer])?;
+
+        for (upper_node, lower_node) in &self.edges[fixed_layer.min(free_layer)] {
+            let (fixed_node, free_node) = if fixed_layer < free_layer {
+                (*upper_node, *lower_node)
+            } else {
+                (*lower_node, *upper_node)
+            };
+            graph.add_edge(fixed_node, number_of_fixed_nodes + free_node)?;
+        }
+
+        Ok(graph)
+    }
+
+    /// Computes the total number of crossings between all adjacent layers for the current orderings
+    pub fn compute_number_of_crossings(&self) -> Result<usize, Error> {
+        let mut number_of_crossings = 0;
+
+        for layer in 1..self.number_of_layers() {
+            let graph = self.bipartite_graph(layer - 1, layer)?;
+            let ordering: Vec<usize> = self.orderings[layer]
+                .iter()
+                .map(|node| node + graph.number_of_fixed_nodes())
+                .collect();
+            number_of_crossings += graph.compute_number_of_crossings_for_ordering(&ordering)?;
+        }
+
+        Ok(number_of_crossings)
+    }
+
+    /// Reorders every layer once in the given direction and returns the total number of crossings afterwards
+    pub fn sweep(&mut self, direction: SweepDirection) -> Result<usize, Error> {
+        let steps: Vec<(usize, usize)> = match direction {
+            SweepDirection::Down => (1..self.number_of_layers())
+                .map(|layer| (layer - 1, layer))
+                .collect(),
+            SweepDirection::Up => (0..self.number_of_layers().saturating_sub(1))
+                .rev()
+                .map(|layer| (layer + 1, layer))
+                .collect(),
+        };
+
+        for (fixed_layer, free_layer) in steps {
+            let graph = self.bipartite_graph(fixed_layer, free_layer)?;
+            // The free node offsets in the penalty digraph are exactly the indices in the free layer.
+            self.orderings[free_layer] = PenaltyDigraph::from_graph(&graph).sort_fas();
+        }
+
+        self.compute_number_of_crossings()
+    }
+
+    /// Runs `number_of_sweeps` sweeps, alternating between down and up sweeps (starting with a down sweep)
+    ///
+    /// Returns the total number of crossings after each sweep.
+    /// A sweep can make the drawing worse, so the best orderings that were found are restored at the end.
+    pub fn minimize_crossings(&mut self, number_of_sweeps: usize) -> Result<Vec<usize>, Error> {
+        let mut best_number_of_crossings = self.compute_number_of_crossings()?;
+        let mut best_orderings = self.orderings.clone();
+        let mut crossings_per_sweep = Vec::with_capacity(number_of_sweeps);
+
+        for sweep in 0..number_of_sweeps {
+            let direction = if sweep % 2 == 0 {
+                SweepDirection::Down
+            } else {
+                SweepDirection::Up
+            };
+            let number_of_crossings = self.sweep(direction)?;
+            crossings_per_sweep.push(number_of_crossings);
+
+            if number_of_crossings < best_number_of_crossings {
+                best_number_of_crossings = number_of_crossings;
+                best_orderings.clone_from(&self.orderings);
+            }
+        }
+
+        self.orderings = best_orderings;
+        Ok(crossings_per_sweep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Three layers of three nodes, both edge layers connect node `i` with node `2 - i` (3 crossings each)
+    fn reversed_drawing() -> LayeredGraph {
+        let mut layered_graph = LayeredGraph::new(&[3, 3, 3]);
+        for layer in 0..2 {
+            for node in 0..3 {
+                layered_graph.add_edge(layer, node, 2 - node).unwrap();
+            }
+        }
+        layered_graph
+    }
+
+    #[test]
+    fn crossings_of_a_known_drawing() {
+        let mut layered_graph = reversed_drawing();
+        assert_eq!(layered_graph.compute_number_of_crossings().unwrap(), 6);
+
+        layered_graph.set_ordering(1, &[2, 1, 0]).unwrap();
+        assert_eq!(layered_graph.compute_number_of_crossings().unwrap(), 0);
+    }
+
+    #[test]
+    fn minimize_crossings_never_increases_the_crossings() {
+        let mut layered_graph = reversed_drawing();
+        layered_graph.minimize_crossings(4).unwrap();
+        assert_eq!(layered_graph.compute_number_of_crossings().unwrap(), 0);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            let layer_sizes: Vec<usize> = (0..rng.gen_range(2..5)).map(|_| rng.gen_range(1..6)).collect();
+            let mut layered_graph = LayeredGraph::new(&layer_sizes);
+            for layer in 0..layer_sizes.len() - 1 {
+                for _ in 0..layer_sizes[layer] * 2 {
+                    let upper_node = rng.gen_range(0..layer_sizes[layer]);
+                    let lower_node = rng.gen_range(0..layer_sizes[layer + 1]);
+                    layered_graph.add_edge(layer, upper_node, lower_node).unwrap();
+                }
+            }
+            let initial_number_of_crossings = layered_graph.compute_number_of_crossings().unwrap();
+
+            let crossings_per_sweep = layered_graph.minimize_crossings(5).unwrap();
+
+            let number_of_crossings = layered_graph.compute_number_of_crossings().unwrap();
+            assert!(number_of_crossings <= initial_number_of_crossings);
+            assert_eq!(
+                number_of_crossings,
+                crossings_per_sweep.into_iter().fold(initial_number_of_crossings, usize::min)
+            );
+        }
+    }
+
+    #[test]
+    fn bipartite_graph_keeps_the_layer_orderings() {
+        let mut layered_graph = reversed_drawing();
+        layered_graph.set_ordering(0, &[1, 2, 0]).unwrap();
+        layered_graph.set_ordering(1, &[2, 0, 1]).unwrap();
+
+        let down_graph = layered_graph.bipartite_graph(0, 1).unwrap();
+        assert_eq!(down_graph.fixed_node_ordering(), vec![1, 2, 0]);
+        assert_eq!(down_graph.edges().collect::<Vec<_>>(), vec![(0, 5), (1, 4), (2, 3)]);
+        // In the layer orderings, the edges end at the positions 2, 1 and 0 of the lower layer, so every pair crosses
+        let ordering: Vec<usize> = [2, 0, 1].iter().map(|node| node + 3).collect();
+        assert_eq!(down_graph.compute_number_of_crossings_for_ordering(&ordering).unwrap(), 3);
+
+        let up_graph = layered_graph.bipartite_graph(1, 0).unwrap();
+        assert_eq!(up_graph.fixed_node_ordering(), vec![2, 0, 1]);
+        assert_eq!(up_graph.edges().collect::<Vec<_>>(), vec![(0, 5), (1, 4), (2, 3)]);
+
+        assert!(layered_graph.bipartite_graph(0, 2).is_err());
+    }
+}
@@ -1,4 +1,12 @@
 pub mod graph;
 pub mod error;
 pub mod graph_builder;
-pub mod application;
\ No newline at end of file
+pub mod application;
+pub mod benchmark_runner;
+pub mod layered_graph;
+pub mod heuristics;
+pub mod verify;
+pub mod report;
+pub mod plot;
+pub mod export;
+pub mod memory;
\ No newline at end of file
@@ -1,12 +1,105 @@
-use pace::application::Application;
+use std::{env, process};
 
+use pace::{application::Application, error::Error, graph::penalty_digraph::FasMethod};
 
-fn main(){
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("Error: {error}");
+        process::exit(1);
+    }
+}
+
+/// Runs the command given by the command line arguments
+fn run() -> Result<(), Error> {
     let application = Application::new();
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.first().map(String::as_str) {
+        Some("bench") => {
+            let suite_files = &arguments[1..];
+            if suite_files.is_empty() {
+                return Err(Error::ValueError(
+                    "Usage: bench <suite.json> [<suite.json> ...]".to_string(),
+                ));
+            }
+            for suite_file in suite_files {
+                application.run_benchmark_suite(suite_file)?;
+            }
+        }
+        Some("report") => {
+            let mut result_files = Vec::new();
+            let mut csv_output = None;
+            let mut remaining_arguments = arguments[1..].iter();
+            while let Some(argument) = remaining_arguments.next() {
+                if argument == "--csv" {
+                    csv_output = remaining_arguments.next().map(String::as_str);
+                } else {
+                    result_files.push(argument.clone());
+                }
+            }
+            if result_files.is_empty() || (arguments.contains(&"--csv".to_string()) && csv_output.is_none()) {
+                return Err(Error::ValueError(
+                    "Usage: report <results.json> [<results.json> ...] [--csv <output.csv>]".to_string(),
+                ));
+            }
+            application.report_benchmark_results(&result_files, csv_output)?;
+        }
+        Some("chart") => {
+            let [result_file, x_axis, output] = &arguments[1..] else {
+                return Err(Error::ValueError(
+                    "Usage: chart <results.json> <fixed_nodes|free_nodes|nodes|edges> <output.svg>".to_string(),
+                ));
+            };
+            application.chart_benchmark_results(result_file, x_axis.parse()?, output)?;
+        }
+        Some("draw") => {
+            let (graph_file, output, solution_file) = match &arguments[1..] {
+                [graph_file, output] => (graph_file, output, None),
+                [graph_file, output, solution_file] => (graph_file, output, Some(solution_file.as_str())),
+                _ => {
+                    return Err(Error::ValueError(
+                        "Usage: draw <graph.gr> <output.svg|output.dot> [<solution.sol>]".to_string(),
+                    ))
+                }
+            };
+            application.draw_graph(graph_file, output, solution_file)?;
+        }
+        Some("digraph") => {
+            let (graph_file, output, method) = match &arguments[1..] {
+                [graph_file, output] => (graph_file, output, FasMethod::SortFas),
+                [graph_file, output, method] => (graph_file, output, method.parse()?),
+                _ => {
+                    return Err(Error::ValueError(
+                        "Usage: digraph <graph.gr> <output.dot> [<fas_method>]".to_string(),
+                    ))
+                }
+            };
+            application.draw_penalty_digraph(graph_file, output, method)?;
+        }
+        Some("analyze") => {
+            let (graph_file, as_json) = match &arguments[1..] {
+                [graph_file] => (graph_file, false),
+                [graph_file, flag] if flag == "--json" => (graph_file, true),
+                _ => {
+                    return Err(Error::ValueError(
+                        "Usage: analyze <graph.gr> [--json]".to_string(),
+                    ))
+                }
+            };
+            application.analyze_graph(graph_file, as_json)?;
+        }
+        Some(command) => {
+            return Err(Error::ValueError(format!("Unknown command '{command}'")));
+        }
+        None => {
+            application.run_tests_with_same_edges_and_free_nodes()?;
+            application.run_on_specific_case(500, 20)?;
+            application.run_tests_on_tiny_test_set()?;
+            application.run_tests_with_same_edges()?;
+            application.run_tests_with_same_nodes()?;
+        }
+    }
 
-    application.run_tests_with_same_edges_and_free_nodes().unwrap();
-    application.run_on_specific_case(500, 20).unwrap();
-    application.run_tests_on_tiny_test_set().unwrap();
-    application.run_tests_with_same_edges().unwrap();
-    application.run_tests_with_same_nodes().unwrap();
+    Ok(())
 }
@@ -0,0 +1,140 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static TOTAL_ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Allocator that forwards to the system allocator and counts the allocated bytes
+///
+/// It is registered as the global allocator if the `count-allocations` feature is enabled.
+pub struct CountingAllocator;
+
+/// Memory usage during one phase of a benchmark
+///
+/// Values that can not be measured are `None`: the peak RSS is read from `/proc/self/status` (Linux only),
+/// the allocated bytes are only counted with the `count-allocations` feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseMemory {
+    /// Peak resident set size of the process during the phase
+    pub peak_rss_bytes: Option<u64>,
+    /// Peak number of heap bytes in use during the phase, including the ones allocated before the phase
+    pub peak_allocated_bytes: Option<u64>,
+    /// Number of heap bytes allocated during the phase (without subtracting the freed ones)
+    pub allocated_bytes: Option<u64>,
+}
+
+/// Measures the memory usage of consecutive phases
+///
+/// Only one tracker should be active at a time, since starting a phase resets the process-wide peaks.
+pub struct MemoryTracker {
+    total_allocated_bytes_at_start: u64,
+    is_peak_rss_reset: bool,
+}
+
+// CONSTRUCTORS
+impl MemoryTracker {
+    /// Constructs a new `MemoryTracker` and starts the first phase
+    pub fn start() -> MemoryTracker {
+        PEAK_ALLOCATED_BYTES.store(ALLOCATED_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+        // Writing 5 to clear_refs resets the peak RSS (VmHWM) to the current RSS
+        let is_peak_rss_reset = fs::write("/proc/self/clear_refs", "5").is_ok();
+
+        MemoryTracker {
+            total_allocated_bytes_at_start: TOTAL_ALLOCATED_BYTES.load(Ordering::Relaxed),
+            is_peak_rss_reset,
+        }
+    }
+}
+
+// PUBLIC METHODS
+impl MemoryTracker {
+    /// Returns the memory usage since the start of the current phase and starts the next phase
+    pub fn finish_phase(&mut self) -> PhaseMemory {
+        let is_counting_allocations = cfg!(feature = "count-allocations");
+        let phase_memory = PhaseMemory {
+            peak_rss_bytes: if self.is_peak_rss_reset { read_peak_rss_bytes() } else { None },
+            peak_allocated_bytes: is_counting_allocations.then(|| PEAK_ALLOCATED_BYTES.load(Ordering::Relaxed)),
+            allocated_bytes: is_counting_allocations
+                .then(|| TOTAL_ALLOCATED_BYTES.load(Ordering::Relaxed) - self.total_allocated_bytes_at_start),
+        };
+
+        *self = MemoryTracker::start();
+        phase_memory
+    }
+}
+
+impl PhaseMemory {
+    /// Combines the measurements of two runs of the same phase by taking the maximum of each value
+    pub fn max(self, other: PhaseMemory) -> PhaseMemory {
+        let max = |value: Option<u64>, other_value: Option<u64>| value.max(other_value);
+
+        PhaseMemory {
+            peak_rss_bytes: max(self.peak_rss_bytes, other.peak_rss_bytes),
+            peak_allocated_bytes: max(self.peak_allocated_bytes, other.peak_allocated_bytes),
+            allocated_bytes: max(self.allocated_bytes, other.allocated_bytes),
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            count_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            count_allocation(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        ALLOCATED_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            ALLOCATED_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+            count_allocation(new_size);
+        }
+        new_pointer
+    }
+}
+
+fn count_allocation(size: usize) {
+    let allocated_bytes = ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+    PEAK_ALLOCATED_BYTES.fetch_max(allocated_bytes, Ordering::Relaxed);
+    TOTAL_ALLOCATED_BYTES.fetch_add(size as u64, Ordering::Relaxed);
+}
+
+/// Reads the peak resident set size (VmHWM) from `/proc/self/status`
+fn read_peak_rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+
+    Some(kilobytes * 1024)
+}
@@ -0,0 +1,388 @@
+use std::{fmt, fs, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{application::BenchmarkStats, error::Error, report::ReportEntry};
+
+/// Colors of the series, used in this order and repeated if there are more series
+const COLORS: [&str; 10] = [
+    "#d62728", "#2ca02c", "#7f7f7f", "#1f77b4", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#bcbd22", "#17becf",
+];
+
+/// Extracts one of the timings (in ms) of a result
+type Timing = fn(&ReportEntry) -> f64;
+
+const MARGIN_LEFT: f64 = 80.0;
+const MARGIN_RIGHT: f64 = 30.0;
+const MARGIN_TOP: f64 = 60.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+
+/// Parameter of the benchmarks that is used as the x-axis of a chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ChartAxis {
+    NumberOfFixedNodes,
+    NumberOfFreeNodes,
+    /// Number of fixed and free nodes
+    NumberOfNodes,
+    NumberOfEdges,
+}
+
+/// Line chart that is rendered as SVG
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineChart {
+    pub title: String,
+    pub subtitle: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub width: f64,
+    pub height: f64,
+    pub series: Vec<Series>,
+}
+
+/// Named sequence of points of a `LineChart`, connected in the order of their x-coordinates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    pub name: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+// CONSTRUCTORS
+impl LineChart {
+    /// Constructs a new empty `LineChart` of 800x600 pixels
+    pub fn new(title: &str, x_label: &str, y_label: &str) -> LineChart {
+        LineChart {
+            title: title.to_string(),
+            subtitle: String::new(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            width: 800.0,
+            height: 600.0,
+            series: Vec::new(),
+        }
+    }
+
+    /// Constructs a chart of the overall, loading, reduction and ordering time (in ms) of benchmark results
+    ///
+    /// Results of different solvers get their own series, prefixed by the name of the solver.
+    pub fn timing_chart(benchmark_stats: &[BenchmarkStats], x_axis: ChartAxis) -> LineChart {
+        let mut chart = LineChart::new(&format!("Time based on {}", x_axis.label()), x_axis.label(), "time, ms");
+        chart.subtitle = x_axis.describe_constant_parameters(benchmark_stats);
+
+        let mut solvers: Vec<&str> = Vec::new();
+        for benchmark in benchmark_stats {
+            if !solvers.contains(&benchmark.solver.as_str()) {
+                solvers.push(&benchmark.solver);
+            }
+        }
+
+        let timings: [(&str, Timing); 4] = [
+            ("Overall", |entry| entry.loading_ms + entry.time_ms()),
+            ("Loading", |entry| entry.loading_ms),
+            ("Reduction", |entry| entry.reduction_ms),
+            ("Ordering", |entry| entry.ordering_ms),
+        ];
+        for solver in &solvers {
+            let entries: Vec<(f64, ReportEntry)> = benchmark_stats
+                .iter()
+                .filter(|benchmark| benchmark.solver == *solver)
+                .map(|benchmark| (x_axis.value(benchmark), ReportEntry::from_benchmark_stats(benchmark)))
+                .collect();
+
+            for (timing_name, timing) in timings {
+                let name = if solvers.len() > 1 {
+                    format!("{}: {timing_name}", solver_name(solver))
+                } else {
+                    timing_name.to_string()
+                };
+                chart.add_series(&name, entries.iter().map(|(x, entry)| (*x, timing(entry))).collect());
+            }
+        }
+
+        chart
+    }
+}
+
+// PUBLIC METHODS
+impl LineChart {
+    /// Adds a series, the points are sorted by their x-coordinates
+    pub fn add_series(&mut self, name: &str, mut points: Vec<(f64, f64)>) {
+        points.sort_by(|(x1, _), (x2, _)| x1.total_cmp(x2));
+        self.series.push(Series {
+            name: name.to_string(),
+            points,
+        });
+    }
+
+    /// Renders the chart with a grid, labelled axes and a legend
+    ///
+    /// Both axes start at 0 (or the smallest value, if it is negative) and end at a round number.
+    pub fn to_svg(&self) -> String {
+        let points = self.series.iter().flat_map(|series| series.points.iter());
+        let (x_ticks, x_range) = axis_ticks(points.clone().map(|(x, _)| *x));
+        let (y_ticks, y_range) = axis_ticks(points.map(|(_, y)| *y));
+
+        let plot_width = self.width - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = self.height - MARGIN_TOP - MARGIN_BOTTOM;
+        let to_pixels = |(x, y): (f64, f64)| {
+            (
+                MARGIN_LEFT + (x - x_range.0) / (x_range.1 - x_range.0) * plot_width,
+                MARGIN_TOP + plot_height - (y - y_range.0) / (y_range.1 - y_range.0) * plot_height,
+            )
+        };
+
+        let mut svg = vec![
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+                self.width, self.height
+            ),
+            format!(r#"<rect width="{}" height="{}" fill="white"/>"#, self.width, self.height),
+            format!(
+                r#"<text x="{}" y="30" text-anchor="middle" font-size="18">{}</text>"#,
+                self.width / 2.0,
+                escape_xml(&self.title)
+            ),
+            format!(
+                r#"<text x="{}" y="50" text-anchor="end" font-size="13">{}</text>"#,
+                self.width - MARGIN_RIGHT,
+                escape_xml(&self.subtitle)
+            ),
+        ];
+
+        for x_tick in &x_ticks {
+            let (x, _) = to_pixels((*x_tick, y_range.0));
+            svg.push(format!(
+                r##"<line x1="{x:.1}" y1="{MARGIN_TOP}" x2="{x:.1}" y2="{:.1}" stroke="#cccccc"/>"##,
+                MARGIN_TOP + plot_height
+            ));
+            svg.push(format!(
+                r#"<text x="{x:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                MARGIN_TOP + plot_height + 18.0,
+                format_tick(*x_tick)
+            ));
+        }
+        for y_tick in &y_ticks {
+            let (_, y) = to_pixels((x_range.0, *y_tick));
+            svg.push(format!(
+                r##"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#cccccc"/>"##,
+                MARGIN_LEFT + plot_width
+            ));
+            svg.push(format!(
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+                MARGIN_LEFT - 6.0,
+                y + 4.0,
+                format_tick(*y_tick)
+            ));
+        }
+        svg.push(format!(
+            r#"<rect x="{MARGIN_LEFT}" y="{MARGIN_TOP}" width="{plot_width:.1}" height="{plot_height:.1}" fill="none" stroke="black"/>"#
+        ));
+        svg.push(format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            MARGIN_LEFT + plot_width / 2.0,
+            self.height - 15.0,
+            escape_xml(&self.x_label)
+        ));
+        svg.push(format!(
+            r#"<text x="20" y="{0:.1}" text-anchor="middle" transform="rotate(-90 20 {0:.1})">{1}</text>"#,
+            MARGIN_TOP + plot_height / 2.0,
+            escape_xml(&self.y_label)
+        ));
+
+        for (series_index, series) in self.series.iter().enumerate() {
+            let coordinates: Vec<String> = series
+                .points
+                .iter()
+                .map(|point| {
+                    let (x, y) = to_pixels(*point);
+                    format!("{x:.1},{y:.1}")
+                })
+                .collect();
+            svg.push(format!(
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+                coordinates.join(" "),
+                COLORS[series_index % COLORS.len()]
+            ));
+        }
+
+        if !self.series.is_empty() {
+            let longest_name = self.series.iter().map(|series| series.name.chars().count()).max().unwrap_or(0);
+            let legend_width = 40.0 + 7.0 * longest_name as f64;
+            let legend_x = MARGIN_LEFT + 10.0;
+            svg.push(format!(
+                r#"<rect x="{legend_x}" y="{}" width="{legend_width}" height="{}" fill="white" stroke="black"/>"#,
+                MARGIN_TOP + 10.0,
+                10.0 + 18.0 * self.series.len() as f64
+            ));
+            for (series_index, series) in self.series.iter().enumerate() {
+                let y = MARGIN_TOP + 28.0 + 18.0 * series_index as f64;
+                svg.push(format!(
+                    r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="{}" stroke-width="1.5"/>"#,
+                    legend_x + 8.0,
+                    legend_x + 28.0,
+                    COLORS[series_index % COLORS.len()]
+                ));
+                svg.push(format!(
+                    r#"<text x="{}" y="{}">{}</text>"#,
+                    legend_x + 34.0,
+                    y + 4.0,
+                    escape_xml(&series.name)
+                ));
+            }
+        }
+
+        svg.push("</svg>".to_string());
+        svg.join("\n") + "\n"
+    }
+
+    /// Writes the chart to an SVG file
+    pub fn write_svg(&self, filename: &str) -> Result<(), Error> {
+        fs::write(filename, self.to_svg()).map_err(Error::with_path(filename))?;
+        Ok(())
+    }
+}
+
+impl ChartAxis {
+    /// Returns the value of the parameter for a benchmark result
+    pub fn value(&self, benchmark: &BenchmarkStats) -> f64 {
+        let value = match self {
+            ChartAxis::NumberOfFixedNodes => benchmark.number_of_fixed_nodes,
+            ChartAxis::NumberOfFreeNodes => benchmark.number_of_free_nodes,
+            ChartAxis::NumberOfNodes => benchmark.number_of_fixed_nodes + benchmark.number_of_free_nodes,
+            ChartAxis::NumberOfEdges => benchmark.number_of_edges,
+        };
+        value as f64
+    }
+
+    /// Returns the label of the axis
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChartAxis::NumberOfFixedNodes => "#fixed nodes",
+            ChartAxis::NumberOfFreeNodes => "#free nodes",
+            ChartAxis::NumberOfNodes => "#nodes",
+            ChartAxis::NumberOfEdges => "#edges",
+        }
+    }
+
+    /// Describes the parameters that are not on the axis, if they are the same for all results
+    fn describe_constant_parameters(&self, benchmark_stats: &[BenchmarkStats]) -> String {
+        let constant_value = |value: fn(&BenchmarkStats) -> usize| -> Option<usize> {
+            let first_value = value(benchmark_stats.first()?);
+            benchmark_stats
+                .iter()
+                .all(|benchmark| value(benchmark) == first_value)
+                .then_some(first_value)
+        };
+        let fixed_nodes = constant_value(|benchmark| benchmark.number_of_fixed_nodes);
+        let free_nodes = constant_value(|benchmark| benchmark.number_of_free_nodes);
+        let edges = constant_value(|benchmark| benchmark.number_of_edges);
+
+        let description = match self {
+            ChartAxis::NumberOfFixedNodes => free_nodes
+                .zip(edges)
+                .map(|(free_nodes, edges)| format!("{free_nodes} free nodes and {edges} edges")),
+            ChartAxis::NumberOfFreeNodes => fixed_nodes
+                .zip(edges)
+                .map(|(fixed_nodes, edges)| format!("{fixed_nodes} fixed nodes and {edges} edges")),
+            ChartAxis::NumberOfNodes => edges.map(|edges| format!("{edges} edges")),
+            ChartAxis::NumberOfEdges => fixed_nodes.zip(free_nodes).map(|(fixed_nodes, free_nodes)| {
+                if fixed_nodes == free_nodes {
+                    format!("{fixed_nodes} fixed and free nodes")
+                } else {
+                    format!("{fixed_nodes} fixed and {free_nodes} free nodes")
+                }
+            }),
+        };
+        description
+            .map(|description| format!("with {description}"))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ChartAxis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChartAxis::NumberOfFixedNodes => write!(f, "fixed_nodes"),
+            ChartAxis::NumberOfFreeNodes => write!(f, "free_nodes"),
+            ChartAxis::NumberOfNodes => write!(f, "nodes"),
+            ChartAxis::NumberOfEdges => write!(f, "edges"),
+        }
+    }
+}
+
+impl FromStr for ChartAxis {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "fixed_nodes" => Ok(ChartAxis::NumberOfFixedNodes),
+            "free_nodes" => Ok(ChartAxis::NumberOfFreeNodes),
+            "nodes" => Ok(ChartAxis::NumberOfNodes),
+            "edges" => Ok(ChartAxis::NumberOfEdges),
+            _ => Err(Error::ValueError(format!(
+                "Unknown chart axis '{name}', expected fixed_nodes, free_nodes, nodes or edges"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for ChartAxis {
+    type Error = Error;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+impl From<ChartAxis> for String {
+    fn from(axis: ChartAxis) -> Self {
+        axis.to_string()
+    }
+}
+
+/// Results written before the solver was recorded come from `sort_fas`
+fn solver_name(solver: &str) -> &str {
+    if solver.is_empty() {
+        "sort_fas"
+    } else {
+        solver
+    }
+}
+
+/// Returns about 10 evenly spaced round tick values that cover the values, together with the range of the axis
+fn axis_ticks(values: impl Iterator<Item = f64>) -> (Vec<f64>, (f64, f64)) {
+    let (minimum, maximum) = values.fold((0.0, 0.0), |(minimum, maximum): (f64, f64), value| {
+        (minimum.min(value), maximum.max(value))
+    });
+    if maximum - minimum <= 0.0 {
+        return (vec![0.0, 1.0], (0.0, 1.0));
+    }
+
+    let rough_step = (maximum - minimum) / 10.0;
+    let magnitude = 10f64.powf(rough_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough_step)
+        .unwrap_or(10.0 * magnitude);
+
+    let first_tick = (minimum / step).floor() * step;
+    let last_tick = (maximum / step).ceil() * step;
+    let number_of_ticks = ((last_tick - first_tick) / step).round() as usize + 1;
+    let ticks = (0..number_of_ticks).map(|tick| first_tick + tick as f64 * step).collect();
+
+    (ticks, (first_tick, last_tick))
+}
+
+/// Formats a tick value without unnecessary decimals
+fn format_tick(value: f64) -> String {
+    let formatted = format!("{value:.3}");
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
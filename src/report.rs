@@ -0,0 +1,375 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    application::{BenchmarkStats, SampleSummary},
+    benchmark_runner::read_json_lines,
+    error::Error,
+};
+
+/// Comparison of the benchmark results of one or more result files, e.g. of different versions
+///
+/// Results of the same generator, solver and graph size are compared, relative differences refer to the first file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub sources: Vec<String>,
+    pub rows: Vec<ReportRow>,
+}
+
+/// Results of one configuration and solver, with one entry per source (`None` if the source has no such result)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportRow {
+    pub generator: String,
+    pub solver: String,
+    pub number_of_fixed_nodes: usize,
+    pub number_of_free_nodes: usize,
+    pub number_of_edges: usize,
+    pub entries: Vec<Option<ReportEntry>>,
+}
+
+/// Mean timings (in milliseconds) and mean number of crossings of a result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportEntry {
+    pub repetitions: usize,
+    pub loading_ms: f64,
+    pub reduction_ms: f64,
+    pub ordering_ms: f64,
+    pub number_of_crossings: Option<f64>,
+}
+
+/// Total time (in milliseconds) and crossings of a solver per source,
+/// the crossings are `None` if they were not computed for all configurations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolverTotal {
+    pub solver: String,
+    pub time_ms: Vec<f64>,
+    pub number_of_crossings: Vec<Option<f64>>,
+}
+
+// CONSTRUCTORS
+impl BenchmarkReport {
+    /// Constructs a new `BenchmarkReport` from named lists of results
+    ///
+    /// Results without generator and solver were written before these were recorded and come from `sort_fas` on random graphs.
+    pub fn new(sources: Vec<(String, Vec<BenchmarkStats>)>) -> BenchmarkReport {
+        let number_of_sources = sources.len();
+        let mut rows: Vec<ReportRow> = Vec::new();
+        let mut row_indices: HashMap<(String, String, usize, usize, usize), usize> = HashMap::new();
+        let mut source_names = Vec::new();
+
+        for (source_index, (source_name, benchmark_stats)) in sources.into_iter().enumerate() {
+            source_names.push(source_name);
+            for benchmark in benchmark_stats {
+                let generator = match benchmark.generator.as_str() {
+                    "" => "random".to_string(),
+                    generator => generator.to_string(),
+                };
+                let solver = match benchmark.solver.as_str() {
+                    "" => "sort_fas".to_string(),
+                    solver => solver.to_string(),
+                };
+                let key = (
+                    generator.clone(),
+                    solver.clone(),
+                    benchmark.number_of_fixed_nodes,
+                    benchmark.number_of_free_nodes,
+                    benchmark.number_of_edges,
+                );
+                let row_index = *row_indices.entry(key).or_insert_with(|| {
+                    rows.push(ReportRow {
+                        generator,
+                        solver,
+                        number_of_fixed_nodes: benchmark.number_of_fixed_nodes,
+                        number_of_free_nodes: benchmark.number_of_free_nodes,
+                        number_of_edges: benchmark.number_of_edges,
+                        entries: vec![None; number_of_sources],
+                    });
+                    rows.len() - 1
+                });
+
+                let entry = &mut rows[row_index].entries[source_index];
+                if entry.is_none() {
+                    *entry = Some(ReportEntry::from_benchmark_stats(&benchmark));
+                }
+            }
+        }
+
+        BenchmarkReport {
+            sources: source_names,
+            rows,
+        }
+    }
+
+    /// Constructs a new `BenchmarkReport` from result files, which contain either a JSON array or JSON Lines
+    pub fn from_files(filenames: &[String]) -> Result<BenchmarkReport, Error> {
+        let sources = filenames
+            .iter()
+            .map(|filename| Ok((filename.clone(), read_benchmark_results(filename)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(BenchmarkReport::new(sources))
+    }
+}
+
+// PUBLIC METHODS
+impl BenchmarkReport {
+    /// Writes one line per result (configuration, solver and source) to a CSV file
+    ///
+    /// The relative differences of the time (reduction and ordering) and the crossings refer to the first source
+    /// and are empty if the first source has no such result.
+    pub fn write_csv(&self, filename: &str) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(filename).map_err(Error::with_path(filename))?);
+        writeln!(
+            writer,
+            "source,generator,solver,number_of_fixed_nodes,number_of_free_nodes,number_of_edges,repetitions,\
+             loading_ms,reduction_ms,ordering_ms,time_ms,number_of_crossings,time_change,crossings_change"
+        )?;
+
+        for row in &self.rows {
+            let baseline = row.entries.first().and_then(Option::as_ref);
+            for (source, entry) in self.sources.iter().zip(&row.entries) {
+                let Some(entry) = entry else {
+                    continue;
+                };
+                let (time_change, crossings_change) = entry.relative_changes(baseline);
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{:.3},{:.3},{:.3},{:.3},{},{},{}",
+                    escape_csv(source),
+                    escape_csv(&row.generator),
+                    escape_csv(&row.solver),
+                    row.number_of_fixed_nodes,
+                    row.number_of_free_nodes,
+                    row.number_of_edges,
+                    entry.repetitions,
+                    entry.loading_ms,
+                    entry.reduction_ms,
+                    entry.ordering_ms,
+                    entry.time_ms(),
+                    entry.number_of_crossings.map(|value| format!("{value:.1}")).unwrap_or_default(),
+                    time_change.map(|change| format!("{change:.4}")).unwrap_or_default(),
+                    crossings_change.map(|change| format!("{change:.4}")).unwrap_or_default(),
+                )?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Sums up the time and crossings per solver and source over the configurations that all sources have results for
+    pub fn solver_totals(&self) -> Vec<SolverTotal> {
+        let mut totals: Vec<SolverTotal> = Vec::new();
+
+        for row in &self.rows {
+            let Some(entries) = row.entries.iter().cloned().collect::<Option<Vec<ReportEntry>>>() else {
+                continue;
+            };
+            let total_index = match totals.iter().position(|total| total.solver == row.solver) {
+                Some(total_index) => total_index,
+                None => {
+                    totals.push(SolverTotal {
+                        solver: row.solver.clone(),
+                        time_ms: vec![0.0; self.sources.len()],
+                        number_of_crossings: vec![Some(0.0); self.sources.len()],
+                    });
+                    totals.len() - 1
+                }
+            };
+
+            let total = &mut totals[total_index];
+            for (source_index, entry) in entries.iter().enumerate() {
+                total.time_ms[source_index] += entry.time_ms();
+                total.number_of_crossings[source_index] = total.number_of_crossings[source_index]
+                    .zip(entry.number_of_crossings)
+                    .map(|(sum, number_of_crossings)| sum + number_of_crossings);
+            }
+        }
+
+        totals
+    }
+}
+
+impl ReportEntry {
+    /// Extracts the means of a result, falling back to the millisecond fields for results without samples
+    pub fn from_benchmark_stats(benchmark: &BenchmarkStats) -> ReportEntry {
+        let milliseconds = |summary: &Option<SampleSummary>, elapsed: u128| match summary {
+            Some(summary) => summary.mean / 1_000_000.0,
+            None => elapsed as f64,
+        };
+
+        ReportEntry {
+            repetitions: benchmark.repetitions.max(1),
+            loading_ms: milliseconds(&benchmark.loading_ns, benchmark.loading_elapsed),
+            reduction_ms: milliseconds(&benchmark.reduction_ns, benchmark.reduction_elapsed),
+            ordering_ms: milliseconds(&benchmark.ordering_ns, benchmark.ordering_elapsed),
+            number_of_crossings: benchmark.number_of_crossings.as_ref().map(|summary| summary.mean),
+        }
+    }
+
+    /// Time of the solver, i.e. reduction and ordering time
+    pub fn time_ms(&self) -> f64 {
+        self.reduction_ms + self.ordering_ms
+    }
+
+    /// Returns the relative differences of the time and the crossings to a baseline entry
+    pub fn relative_changes(&self, baseline: Option<&ReportEntry>) -> (Option<f64>, Option<f64>) {
+        let Some(baseline) = baseline else {
+            return (None, None);
+        };
+
+        (
+            relative_change(baseline.time_ms(), self.time_ms()),
+            baseline
+                .number_of_crossings
+                .zip(self.number_of_crossings)
+                .and_then(|(baseline_crossings, crossings)| relative_change(baseline_crossings, crossings)),
+        )
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    /// Prints a table with the time (in ms) and crossings of every source per configuration and solver,
+    /// followed by the totals per solver
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (source_index, source) in self.sources.iter().enumerate() {
+            writeln!(f, "[{}] {source}", source_index + 1)?;
+        }
+
+        let mut header = vec![
+            "generator".to_string(),
+            "solver".to_string(),
+            "fixed".to_string(),
+            "free".to_string(),
+            "edges".to_string(),
+        ];
+        for source_index in 1..=self.sources.len() {
+            header.push(format!("time [{source_index}]"));
+            header.push(format!("crossings [{source_index}]"));
+            if source_index > 1 {
+                header.push(format!("Δtime [{source_index}]"));
+                header.push(format!("Δcrossings [{source_index}]"));
+            }
+        }
+
+        let mut table = vec![header];
+        for row in &self.rows {
+            let mut cells = vec![
+                row.generator.clone(),
+                row.solver.clone(),
+                row.number_of_fixed_nodes.to_string(),
+                row.number_of_free_nodes.to_string(),
+                row.number_of_edges.to_string(),
+            ];
+            let baseline = row.entries.first().and_then(Option::as_ref);
+            for (source_index, entry) in row.entries.iter().enumerate() {
+                let (time_change, crossings_change) = entry
+                    .as_ref()
+                    .map_or((None, None), |entry| entry.relative_changes(baseline));
+                cells.push(format_optional(entry.as_ref().map(ReportEntry::time_ms), |time_ms| {
+                    format!("{time_ms:.3}")
+                }));
+                cells.push(format_optional(
+                    entry.as_ref().and_then(|entry| entry.number_of_crossings),
+                    |number_of_crossings| format!("{number_of_crossings:.1}"),
+                ));
+                if source_index > 0 {
+                    cells.push(format_optional(time_change, format_percentage));
+                    cells.push(format_optional(crossings_change, format_percentage));
+                }
+            }
+            table.push(cells);
+        }
+        write_table(f, &table)?;
+
+        writeln!(f)?;
+        let mut totals_table = vec![vec!["solver".to_string()]];
+        for source_index in 1..=self.sources.len() {
+            totals_table[0].push(format!("total time [{source_index}]"));
+            totals_table[0].push(format!("total crossings [{source_index}]"));
+        }
+        for total in self.solver_totals() {
+            let mut cells = vec![total.solver];
+            for (time_ms, number_of_crossings) in total.time_ms.into_iter().zip(total.number_of_crossings) {
+                cells.push(format!("{time_ms:.3}"));
+                cells.push(format_optional(number_of_crossings, |number_of_crossings| {
+                    format!("{number_of_crossings:.1}")
+                }));
+            }
+            totals_table.push(cells);
+        }
+        write_table(f, &totals_table)
+    }
+}
+
+/// Reads benchmark results from a file that contains either a JSON array or JSON Lines of `BenchmarkStats`
+pub fn read_benchmark_results(filename: &str) -> Result<Vec<BenchmarkStats>, Error> {
+    let content = fs::read_to_string(filename).map_err(Error::with_path(filename))?;
+    if content.trim_start().starts_with('[') {
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        read_json_lines(filename)
+    }
+}
+
+/// Relative difference of a value to a baseline, `None` if the baseline is 0
+fn relative_change(baseline: f64, value: f64) -> Option<f64> {
+    if baseline == 0.0 {
+        None
+    } else {
+        Some((value - baseline) / baseline)
+    }
+}
+
+fn format_optional<T>(value: Option<T>, format: impl Fn(T) -> String) -> String {
+    value.map_or_else(|| "-".to_string(), format)
+}
+
+fn format_percentage(change: f64) -> String {
+    format!("{:+.1}%", 100.0 * change)
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the rows with right-aligned columns, the first row is the header
+fn write_table(f: &mut fmt::Formatter<'_>, table: &[Vec<String>]) -> fmt::Result {
+    let number_of_columns = table.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..number_of_columns)
+        .map(|column| {
+            table
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (row_index, row) in table.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect();
+        writeln!(f, "{}", cells.join("  "))?;
+        if row_index == 0 {
+            let separators: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            writeln!(f, "{}", separators.join("  "))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{list_nodes, Error},
+    graph::Graph,
+};
+
+/// Line number (1-based) and content of a line of a solution file that is no label
+type InvalidLine = (usize, String);
+
+/// Result of checking a candidate ordering of the free nodes against a `Graph`
+///
+/// All labels are reported in the numbering of the input, i.e. 0-based node indices for `verify_ordering`
+/// and 1-based labels (as in the PACE format) for `verify_solution_file`.
+/// `number_of_crossings` is only computed if the ordering is valid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub number_of_free_nodes: usize,
+    pub number_of_labels: usize,
+    pub missing_labels: Vec<usize>,
+    pub duplicate_labels: Vec<usize>,
+    pub out_of_range_labels: Vec<usize>,
+    pub fixed_node_labels: Vec<usize>,
+    /// Line numbers (1-based) and contents of the lines of a solution file that are no labels
+    pub invalid_lines: Vec<(usize, String)>,
+    pub number_of_crossings: Option<usize>,
+}
+
+impl VerificationReport {
+    /// Checks, if the ordering contains every free node exactly once and nothing else
+    pub fn is_valid(&self) -> bool {
+        self.missing_labels.is_empty()
+            && self.duplicate_labels.is_empty()
+            && self.out_of_range_labels.is_empty()
+            && self.fixed_node_labels.is_empty()
+            && self.invalid_lines.is_empty()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(number_of_crossings) = self.number_of_crossings {
+            return write!(
+                f,
+                "Valid ordering of {} free nodes with {number_of_crossings} crossings",
+                self.number_of_free_nodes
+            );
+        }
+
+        let mut lines = vec![format!(
+            "Invalid ordering: {} labels for {} free nodes",
+            self.number_of_labels, self.number_of_free_nodes
+        )];
+        let problems = [
+            ("Missing free nodes", &self.missing_labels),
+            ("Duplicate labels", &self.duplicate_labels),
+            ("Labels out of range", &self.out_of_range_labels),
+            ("Labels of fixed nodes", &self.fixed_node_labels),
+        ];
+        for (description, labels) in problems {
+            if !labels.is_empty() {
+                lines.push(format!("  {description} ({}): {}", labels.len(), list_nodes(labels)));
+            }
+        }
+        for (line_number, line) in &self.invalid_lines {
+            lines.push(format!("  Invalid line {line_number}: '{line}'"));
+        }
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Checks an ordering of the free nodes, given by their 0-based node indices
+pub fn verify_ordering(graph: &Graph, ordering: &[usize]) -> VerificationReport {
+    verify_labels(graph, ordering, 0, Vec::new())
+}
+
+/// Checks a solution file in the PACE format, which contains the 1-based label of one free node per line
+///
+/// Empty lines and comment lines (starting with 'c') are skipped.
+/// Returns an error only if the file can not be read, all problems of the solution itself are part of the report.
+pub fn verify_solution_file(graph: &Graph, filename: &str) -> Result<VerificationReport, Error> {
+    let (labels, invalid_lines) = read_labels(filename)?;
+    Ok(verify_labels(graph, &labels, 1, invalid_lines))
+}
+
+/// Reads the ordering (as 0-based node indices) from a solution file in the PACE format
+///
+/// Returns `Error::InvalidSolution` with the `VerificationReport`, if the solution is not a valid ordering of the free nodes.
+pub fn read_solution_file(graph: &Graph, filename: &str) -> Result<Vec<usize>, Error> {
+    let (labels, invalid_lines) = read_labels(filename)?;
+    let report = verify_labels(graph, &labels, 1, invalid_lines);
+    if !report.is_valid() {
+        return Err(Error::InvalidSolution {
+            filename: filename.to_string(),
+            report: Box::new(report),
+        });
+    }
+
+    Ok(labels.iter().map(|label| label - 1).collect())
+}
+
+/// Reads the labels of a solution file, together with the line numbers and contents of the lines that are no labels
+fn read_labels(filename: &str) -> Result<(Vec<usize>, Vec<InvalidLine>), Error> {
+    let file = File::open(filename).map_err(Error::with_path(filename))?;
+    let mut labels = Vec::new();
+    let mut invalid_lines = Vec::new();
+
+    for (line_index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        let trimmed_line = line.trim();
+        if trimmed_line.is_empty() || trimmed_line.starts_with('c') {
+            continue;
+        }
+
+        match trimmed_line.parse::<usize>() {
+            Ok(label) => labels.push(label),
+            Err(_) => invalid_lines.push((line_index + 1, line)),
+        }
+    }
+
+    Ok((labels, invalid_lines))
+}
+
+/// Checks the labels, where label `first_label + i` is the node with index `i`
+fn verify_labels(
+    graph: &Graph,
+    labels: &[usize],
+    first_label: usize,
+    invalid_lines: Vec<InvalidLine>,
+) -> VerificationReport {
+    let mut occurrences: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut out_of_range_labels = Vec::new();
+    let mut fixed_node_labels = Vec::new();
+
+    for label in labels {
+        match label.checked_sub(first_label) {
+            Some(node) if node < graph.number_of_fixed_nodes() => fixed_node_labels.push(*label),
+            Some(node) if node < graph.number_of_nodes() => *occurrences.entry(*label).or_default() += 1,
+            _ => out_of_range_labels.push(*label),
+        }
+    }
+    fixed_node_labels.sort_unstable();
+    fixed_node_labels.dedup();
+    out_of_range_labels.sort_unstable();
+    out_of_range_labels.dedup();
+
+    let missing_labels = graph
+        .free_nodes()
+        .map(|node| node + first_label)
+        .filter(|label| !occurrences.contains_key(label))
+        .collect();
+    let duplicate_labels = occurrences
+        .iter()
+        .filter(|(_, count)| **count > 1)
+        .map(|(label, _)| *label)
+        .collect();
+
+    let mut report = VerificationReport {
+        number_of_free_nodes: graph.number_of_free_nodes(),
+        number_of_labels: labels.len(),
+        missing_labels,
+        duplicate_labels,
+        out_of_range_labels,
+        fixed_node_labels,
+        invalid_lines,
+        number_of_crossings: None,
+    };
+    if report.is_valid() {
+        let ordering: Vec<usize> = labels.iter().map(|label| label - first_label).collect();
+        report.number_of_crossings = graph.compute_number_of_crossings_for_ordering(&ordering).ok();
+    }
+
+    report
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    /// Writes a solution file to the temporary directory and returns its name
+    fn write_solution_file(name: &str, content: &str) -> String {
+        let filename = env::temp_dir().join(format!("pace_{}_{name}.sol", process::id()));
+        fs::write(&filename, content).unwrap();
+        filename.to_str().unwrap().to_string()
+    }
+
+    /// Two fixed nodes (labels 1 and 2) and three free nodes (labels 3 to 5)
+    fn graph() -> Graph {
+        let mut graph = Graph::new(2, 3);
+        for (fixed_node_index, free_node_index) in [(0, 4), (1, 2), (1, 3)] {
+            graph.add_edge(fixed_node_index, free_node_index).unwrap();
+        }
+        graph
+    }
+
+    #[test]
+    fn read_solution_file_returns_the_ordering() {
+        let filename = write_solution_file("read_valid", "c comment\n5\n3\n\n4\n");
+
+        assert_eq!(read_solution_file(&graph(), &filename).unwrap(), vec![4, 2, 3]);
+        fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn read_solution_file_reports_invalid_solutions() {
+        let filename = write_solution_file("read_invalid", "3\n3\n1\n");
+
+        match read_solution_file(&graph(), &filename) {
+            Err(Error::InvalidSolution {
+                filename: error_filename,
+                report,
+            }) => {
+                assert_eq!(error_filename, filename);
+                assert_eq!(report.missing_labels, vec![4, 5]);
+                assert_eq!(report.duplicate_labels, vec![3]);
+                assert_eq!(report.fixed_node_labels, vec![1]);
+            }
+            result => panic!("Expected an invalid solution, found {result:?}"),
+        }
+        fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn valid_ordering_reports_the_crossings() {
+        let graph = graph();
+        let report = verify_ordering(&graph, &[4, 2, 3]);
+
+        assert!(report.is_valid());
+        assert_eq!(
+            report.number_of_crossings,
+            Some(graph.compute_number_of_crossings_for_ordering(&[4, 2, 3]).unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_ordering_reports_every_problem() {
+        let report = verify_ordering(&graph(), &[2, 2, 7, 0, 9, 7]);
+
+        assert!(!report.is_valid());
+        assert_eq!(report.number_of_labels, 6);
+        assert_eq!(report.missing_labels, vec![3, 4]);
+        assert_eq!(report.duplicate_labels, vec![2]);
+        assert_eq!(report.out_of_range_labels, vec![7, 9]);
+        assert_eq!(report.fixed_node_labels, vec![0]);
+        assert_eq!(report.number_of_crossings, None);
+    }
+
+    #[test]
+    fn solution_file_labels_start_at_one() {
+        let filename = write_solution_file("labels", "c comment\n0\n2\n3\n4\n5\n6\n");
+
+        let report = verify_solution_file(&graph(), &filename).unwrap();
+        assert_eq!(report.out_of_range_labels, vec![0, 6]);
+        assert_eq!(report.fixed_node_labels, vec![2]);
+        assert!(report.missing_labels.is_empty() && report.duplicate_labels.is_empty());
+        fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn solution_file_reports_invalid_lines() {
+        let filename = write_solution_file("invalid_lines", "3\nfour\n\n4\n5\n-1\n");
+
+        let report = verify_solution_file(&graph(), &filename).unwrap();
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.invalid_lines,
+            vec![(2, "four".to_string()), (6, "-1".to_string())]
+        );
+        assert!(report.missing_labels.is_empty());
+        assert_eq!(report.number_of_crossings, None);
+        fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn missing_solution_file_is_an_error() {
+        assert!(matches!(
+            verify_solution_file(&graph(), "does/not/exist.sol"),
+            Err(Error::PathError { .. })
+        ));
+    }
+}